@@ -0,0 +1,2 @@
+ALTER TABLE progress_logs
+DROP COLUMN note;
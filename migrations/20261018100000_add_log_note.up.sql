@@ -0,0 +1,2 @@
+ALTER TABLE progress_logs
+ADD COLUMN note TEXT;
@@ -0,0 +1,2 @@
+ALTER TABLE commitments
+DROP COLUMN goal_direction;
@@ -0,0 +1,2 @@
+ALTER TABLE commitments
+ADD COLUMN goal_direction TEXT NOT NULL DEFAULT 'at_least' CHECK (goal_direction IN ('at_least', 'at_most'));
@@ -0,0 +1,5 @@
+ALTER TABLE commitments
+DROP COLUMN unit_label;
+
+ALTER TABLE commitments
+DROP COLUMN unit_kind;
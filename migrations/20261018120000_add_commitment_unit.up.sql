@@ -0,0 +1,9 @@
+-- Targets and logged amounts are now expressed in the commitment's unit, the `hours` columns keep
+-- their names for compatibility.
+ALTER TABLE commitments
+ADD COLUMN unit_kind TEXT NOT NULL DEFAULT 'hours' CHECK (
+  unit_kind IN ('hours', 'minutes', 'count', 'distance', 'custom')
+);
+
+ALTER TABLE commitments
+ADD COLUMN unit_label TEXT;
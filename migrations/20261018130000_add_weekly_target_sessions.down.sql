@@ -0,0 +1,2 @@
+ALTER TABLE commitments
+DROP COLUMN weekly_target_sessions;
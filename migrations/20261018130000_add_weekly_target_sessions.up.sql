@@ -0,0 +1,5 @@
+-- Optional number of sessions (distinct days with a log) required every week
+ALTER TABLE commitments
+ADD COLUMN weekly_target_sessions INTEGER CHECK (
+  weekly_target_sessions IS NULL OR weekly_target_sessions > 0
+);
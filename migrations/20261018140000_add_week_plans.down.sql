@@ -0,0 +1 @@
+DROP TABLE week_plans;
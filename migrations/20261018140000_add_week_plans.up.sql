@@ -0,0 +1,10 @@
+-- Per-week override of how a commitment's weekly target is spread across the days, weeks
+-- without any row are split evenly. `weekday` counts from Monday = 0.
+CREATE TABLE week_plans (
+  commitment_id INTEGER NOT NULL,
+  week_start TEXT NOT NULL,
+  weekday INTEGER NOT NULL CHECK (weekday BETWEEN 0 AND 6),
+  planned_hours REAL NOT NULL CHECK (planned_hours >= 0),
+  PRIMARY KEY (commitment_id, week_start, weekday),
+  FOREIGN KEY (commitment_id) REFERENCES commitments(id)
+);
@@ -0,0 +1,5 @@
+ALTER TABLE commitments
+DROP COLUMN carry_over_value;
+
+ALTER TABLE commitments
+DROP COLUMN carry_over;
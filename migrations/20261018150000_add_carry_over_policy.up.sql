@@ -0,0 +1,9 @@
+-- How the balance of past weeks carries over: `capped` keeps at most `carry_over_value` in either
+-- direction, `decay` drops `carry_over_value` percent of it every week.
+ALTER TABLE commitments
+ADD COLUMN carry_over TEXT NOT NULL DEFAULT 'full' CHECK (
+  carry_over IN ('none', 'full', 'capped', 'decay')
+);
+
+ALTER TABLE commitments
+ADD COLUMN carry_over_value REAL;
@@ -0,0 +1,6 @@
+DROP VIEW commitment_rollups;
+
+DROP VIEW commitment_descendants;
+
+ALTER TABLE commitments
+DROP COLUMN parent_id;
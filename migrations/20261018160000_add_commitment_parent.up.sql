@@ -0,0 +1,22 @@
+ALTER TABLE commitments
+ADD COLUMN parent_id INTEGER REFERENCES commitments(id);
+
+-- Every commitment paired with itself and all of its descendants, so that logs of children count
+-- toward their parents.
+CREATE VIEW commitment_descendants AS
+WITH RECURSIVE tree(root_id, id) AS (
+  SELECT id, id FROM commitments
+  UNION
+  SELECT tree.root_id, c.id
+  FROM commitments c
+  JOIN tree ON c.parent_id = tree.id
+)
+SELECT root_id, id FROM tree;
+
+-- Weekly targets rolled up from the active descendants of every commitment
+CREATE VIEW commitment_rollups AS
+SELECT d.root_id AS id, SUM(c.weekly_target_hours) AS weekly_target_hours
+FROM commitment_descendants d
+JOIN commitments c ON c.id = d.id
+WHERE c.active = 1 OR c.id = d.root_id
+GROUP BY d.root_id;
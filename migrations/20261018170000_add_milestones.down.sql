@@ -0,0 +1,5 @@
+ALTER TABLE commitments
+DROP COLUMN milestone_due;
+
+ALTER TABLE commitments
+DROP COLUMN milestone_total;
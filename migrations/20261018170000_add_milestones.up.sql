@@ -0,0 +1,6 @@
+-- Optional total to reach by a due date, on top of the weekly target
+ALTER TABLE commitments
+ADD COLUMN milestone_total REAL CHECK (milestone_total IS NULL OR milestone_total > 0);
+
+ALTER TABLE commitments
+ADD COLUMN milestone_due TEXT;
@@ -0,0 +1,3 @@
+-- Optional last week of a commitment, it is archived once that week is over
+ALTER TABLE commitments
+ADD COLUMN end_week_monday TEXT;
@@ -0,0 +1 @@
+DROP TABLE inactive_periods;
@@ -0,0 +1,8 @@
+-- When commitments were archived and reactivated, the weeks in between are not counted
+CREATE TABLE inactive_periods (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  commitment_id INTEGER NOT NULL,
+  archived_on TEXT NOT NULL,
+  reactivated_on TEXT,
+  FOREIGN KEY (commitment_id) REFERENCES commitments(id)
+);
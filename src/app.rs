@@ -1,19 +1,35 @@
+use chrono::{Datelike, Duration, Local, NaiveDate};
 use core::fmt;
-use ratatui::widgets::ListState;
+use crossterm::event::KeyEvent;
+use ratatui::{layout::Rect, widgets::ListState};
 use sqlx::SqlitePool;
-use std::time::Instant;
+use std::{collections::HashSet, time::Instant};
 
 use crate::{
-    db::{list_all_commitments_with_week_progress, weekly_stats_for_commitment},
-    model::{CommitmentWithProgress, WeeklyStat},
+    config::{Config, Preset, PresetError, ProgressConfig},
+    db::{
+        archive_ended_commitments, descendant_ids, list_all_commitments_with_week_progress,
+        logs_for_week, plans_for_week, weekly_stats_for_commitment,
+    },
+    keymap::{Action, KeyBuffer, Keymap},
+    model::{CommitmentWithProgress, DeletePreview, ProgressLog, WeekPlan, WeeklyStat},
+    theme::Theme,
+    unit::Unit,
+    util::{fuzzy_positions, get_monday_this_week, tree_order, week_start, weeks_since},
 };
 
-pub type CommitmentDisplayRecord = (CommitmentWithProgress, Vec<WeeklyStat>);
+pub type CommitmentDisplayRecord = (CommitmentWithProgress, Vec<WeeklyStat>, WeekPlan);
 
 #[derive(Debug, Clone)]
 pub enum CreateCommitmentInputField {
     Name,
-    TargetHour,
+}
+
+/// Which chart is shown in the detail pane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailView {
+    Weekly,
+    Cumulative,
 }
 
 #[derive(Debug, Clone)]
@@ -21,28 +37,135 @@ pub enum InputMode {
     Normal,
     LogHours,
     CreateCommitment(CreateCommitmentInputField),
+    /// Waiting for the user to confirm deleting a commitment
+    ConfirmDelete(DeletePreview),
+    /// Choosing one of the quick-log presets from the config file
+    PickPreset,
+    /// Showing every key binding
+    Help,
+    /// Showing the logs of a week picked in the weekly chart
+    WeekLogs(WeekLogs),
+    /// Typing a search that filters the commitment list as it changes
+    Search,
+}
+
+/// Which commitments the list shows, on top of the search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListFilter {
+    All,
+    Active,
+    Archived,
+    /// Active commitments behind this week's plan, or over it for limits
+    Behind,
+}
+
+impl fmt::Display for ListFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListFilter::All => write!(f, "all"),
+            ListFilter::Active => write!(f, "active"),
+            ListFilter::Archived => write!(f, "archived"),
+            ListFilter::Behind => write!(f, "behind"),
+        }
+    }
+}
+
+/// Whether an active commitment is behind where this week's plan has it by today
+fn is_behind_plan(item: &CommitmentDisplayRecord) -> bool {
+    let c = &item.0;
+    if !c.commitment.active || c.commitment.weekly_target_hours <= 0.0 {
+        return false;
+    }
+    let expected = item.2.expected_by(Local::now().weekday(), week_start());
+    let done = c.current_week_total.unwrap_or(0.0);
+    if c.commitment.goal_direction.is_limit() {
+        done > expected
+    } else {
+        done < expected
+    }
+}
+
+/// The logs of a commitment and its sub-commitments in one week
+#[derive(Debug, Clone)]
+pub struct WeekLogs {
+    pub name: String,
+    pub unit: Unit,
+    pub week: NaiveDate,
+    pub logs: Vec<ProgressLog>,
+}
+
+/// A button in one of the overlays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Button {
+    Log,
+    Delete,
+    Cancel,
+    Close,
+}
+
+/// What is drawn in a part of the screen that reacts to the mouse
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickTarget {
+    /// The commitments list, scrolled with the wheel
+    List,
+    /// A row of the commitments list, by its index among the visible rows
+    Row(usize),
+    /// The weekly chart, scrolled through the weeks with the wheel
+    Chart,
+    /// The bar of a week in the weekly chart
+    Week(NaiveDate),
+    /// A key hint in the footer
+    Action(Action),
+    /// A preset in the preset picker, by its index
+    Preset(usize),
+    Button(Button),
 }
 
 impl fmt::Display for InputMode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             InputMode::Normal => write!(f, "NORMAL"),
-            InputMode::LogHours => write!(f, "LOG HOUR"),
+            InputMode::LogHours => write!(f, "LOG"),
             InputMode::CreateCommitment(_) => write!(f, "CREATE"),
+            InputMode::ConfirmDelete(_) => write!(f, "DELETE"),
+            InputMode::PickPreset => write!(f, "PRESET"),
+            InputMode::Help => write!(f, "HELP"),
+            InputMode::WeekLogs(_) => write!(f, "WEEK"),
+            InputMode::Search => write!(f, "SEARCH"),
         }
     }
 }
 
-const NORMAL_MODE_KEY_MAPS_HINT: &str =
-    "q: quit | j/k: move | c: add commitment | l: log | r: reactivate | a: archive";
 const LOG_MODE_KEY_MAPS_HINT: &str =
-    "esc: quit | numeric characters (0-9, .): Hours Input | ctrl-u: clear";
+    "esc: quit | amount (e.g. 1.5, 1h30m, 5km): Input | enter: log | ctrl-u: clear | tab: presets";
+const PICK_PRESET_MODE_KEY_MAPS_HINT: &str = "esc: back | 1-9: log preset | j/k: move | enter: log";
+const CONFIRM_DELETE_MODE_KEY_MAPS_HINT: &str = "y: delete | n/esc: cancel";
+const HELP_MODE_KEY_MAPS_HINT: &str = "esc/q/?: close | j/k: scroll";
+const WEEK_LOGS_MODE_KEY_MAPS_HINT: &str = "esc/q/enter: close";
+const SEARCH_MODE_KEY_MAPS_HINT: &str =
+    "characters: search | enter: keep | esc: cancel | ↑/↓: previous/next match | ctrl-u: clear";
 const CREATE_MODE_COMMITMENT_KEY_MAPS_HINT: &str =
     "esc: quit | <TAB/S-TAB> or <Arrows> to change fields | ctrl-u: clear | characters: Input";
 
+const MIN_CHART_BAR_WIDTH: u16 = 2;
+const MAX_CHART_BAR_WIDTH: u16 = 9;
+const DEFAULT_CHART_BAR_WIDTH: u16 = 5;
+
 pub struct App {
     pool: SqlitePool,
+    /// All the commitments, each one followed by its sub-commitments
     items: Vec<CommitmentDisplayRecord>,
+    /// Depth of every item in the commitment tree, parallel to `items`
+    depths: Vec<usize>,
+    /// Indices into `items` of the rows shown in the list, skipping children of folded parents
+    visible: Vec<usize>,
+    /// Rows of `visible` that match the search and filter, the others are their parents
+    matches: Vec<usize>,
+    /// Fuzzy search on the names in the list
+    pub search: String,
+    pub filter: ListFilter,
+    /// Ids of the parents whose children are hidden
+    collapsed: HashSet<i64>,
     quick_msg: String,
     keymap_msg: String,
     dirty_flag: bool,
@@ -51,21 +174,62 @@ pub struct App {
     pub list_state: ListState,
     pub input_mode: InputMode,
     pub last_refresh: Instant,
+    /// Number of weeks the weekly chart is scrolled back from the current week
+    pub chart_offset: usize,
+    /// Width of each bar in the weekly chart, changed by zooming
+    pub chart_bar_width: u16,
+    /// Number of weeks the weekly chart had room for when it was last drawn
+    pub chart_capacity: usize,
+    pub detail_view: DetailView,
+    /// Quick-log presets from the config file, by name
+    presets: Vec<(String, Preset)>,
+    pub progress: ProgressConfig,
+    pub theme: Theme,
+    pub preset_state: ListState,
+    pub keymap: Keymap,
+    /// Count and keys typed so far of a binding that takes several
+    pub pending_keys: KeyBuffer,
+    /// First line of the help overlay that is shown
+    pub help_scroll: u16,
+    /// Parts of the last drawn frame that react to the mouse, later ones drawn on top
+    click_targets: Vec<(Rect, ClickTarget)>,
 }
 
 impl App {
-    pub async fn new(pool: SqlitePool) -> anyhow::Result<Self> {
+    pub async fn new(pool: SqlitePool, config: &Config) -> anyhow::Result<Self> {
         let mut app = Self {
-            pool: pool,
+            pool,
             items: Vec::new(),
+            depths: Vec::new(),
+            visible: Vec::new(),
+            matches: Vec::new(),
+            search: String::new(),
+            filter: ListFilter::All,
+            collapsed: HashSet::new(),
             quick_msg: String::from("Welcome!"),
-            keymap_msg: String::from(NORMAL_MODE_KEY_MAPS_HINT),
+            keymap_msg: config.keymap().footer_hint(),
             dirty_flag: false,
             list_state: ListState::default(),
             input_mode: InputMode::Normal,
             input_buffer: String::new(),
             input_buffer_1: String::new(),
             last_refresh: Instant::now(),
+            chart_offset: 0,
+            chart_bar_width: DEFAULT_CHART_BAR_WIDTH,
+            chart_capacity: 1,
+            detail_view: DetailView::Weekly,
+            presets: config
+                .presets
+                .iter()
+                .map(|(name, preset)| (name.clone(), preset.clone()))
+                .collect(),
+            preset_state: ListState::default(),
+            progress: config.progress,
+            theme: config.theme(),
+            keymap: config.keymap(),
+            pending_keys: KeyBuffer::default(),
+            help_scroll: 0,
+            click_targets: Vec::new(),
         };
         app.refresh_from_db().await?;
         if !app.items.is_empty() {
@@ -75,15 +239,29 @@ impl App {
     }
 
     async fn refresh_from_db(&mut self) -> anyhow::Result<()> {
+        // The TUI can stay open past the end week of a commitment
+        archive_ended_commitments(&self.pool).await?;
         let commitments_with_progs = list_all_commitments_with_week_progress(&self.pool).await?;
+        let plan_entries = plans_for_week(&self.pool, get_monday_this_week()).await?;
 
-        self.items.clear();
+        let mut items = Vec::new();
         for c_pg in commitments_with_progs {
-            let stats = weekly_stats_for_commitment(&self.pool, c_pg.id).await?;
+            let stats = weekly_stats_for_commitment(&self.pool, c_pg.commitment.id).await?;
+            let plan = WeekPlan::from_entries(
+                c_pg.commitment.id,
+                c_pg.commitment.weekly_target_hours,
+                &plan_entries,
+            );
 
-            self.items.push((c_pg, stats));
+            items.push((c_pg, stats, plan));
         }
-        self.items.sort_by_key(|c| (!c.0.active, c.0.id));
+        items.sort_by_key(|c| (!c.0.commitment.active, c.0.commitment.id));
+
+        (self.items, self.depths) =
+            tree_order(items, |c| c.0.commitment.id, |c| c.0.commitment.parent_id)
+                .into_iter()
+                .unzip();
+        self.rebuild_visible();
 
         self.last_refresh = Instant::now();
 
@@ -126,7 +304,210 @@ impl App {
     /// Get the selected item along of with its historic record
     pub fn get_selected_item(&self) -> Option<&CommitmentDisplayRecord> {
         self.get_selected_index()
-            .and_then(|idx| self.items.get(idx))
+            .and_then(|idx| self.visible.get(idx))
+            .and_then(|&idx| self.items.get(idx))
+    }
+
+    /// The rows of the commitment list, with their depth in the tree and whether they have
+    /// children and are folded
+    pub fn get_visible_items(&self) -> Vec<(&CommitmentDisplayRecord, usize, bool, bool)> {
+        self.visible
+            .iter()
+            .map(|&idx| {
+                let item = &self.items[idx];
+                (
+                    item,
+                    self.depths[idx],
+                    self.has_children(item.0.commitment.id),
+                    self.collapsed.contains(&item.0.commitment.id),
+                )
+            })
+            .collect()
+    }
+
+    fn has_children(&self, id: i64) -> bool {
+        self.items
+            .iter()
+            .any(|c| c.0.commitment.parent_id == Some(id))
+    }
+
+    /// Whether the list is narrowed down by a search or a filter
+    pub fn is_filtering(&self) -> bool {
+        !self.search.is_empty() || self.filter != ListFilter::All
+    }
+
+    /// Whether `item` matches the search and the filter, rather than being shown as a parent
+    pub fn is_match(&self, item: &CommitmentDisplayRecord) -> bool {
+        let passes_filter = match self.filter {
+            ListFilter::All => true,
+            ListFilter::Active => item.0.commitment.active,
+            ListFilter::Archived => !item.0.commitment.active,
+            ListFilter::Behind => is_behind_plan(item),
+        };
+        passes_filter && fuzzy_positions(&self.search, &item.0.commitment.name).is_some()
+    }
+
+    pub fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn rebuild_visible(&mut self) {
+        self.visible.clear();
+        self.matches.clear();
+
+        if self.is_filtering() {
+            // Every match along with its parents so the tree still reads right, ignoring folds
+            let matched: Vec<bool> = self.items.iter().map(|item| self.is_match(item)).collect();
+            let mut shown = matched.clone();
+            let mut child_depth: Option<usize> = None;
+            for idx in (0..self.items.len()).rev() {
+                let depth = self.depths[idx];
+                if child_depth.is_some_and(|d| depth < d) {
+                    shown[idx] = true;
+                }
+                if shown[idx] {
+                    child_depth = Some(depth);
+                }
+            }
+            for idx in (0..self.items.len()).filter(|&idx| shown[idx]) {
+                if matched[idx] {
+                    self.matches.push(self.visible.len());
+                }
+                self.visible.push(idx);
+            }
+        } else {
+            let mut folded_depth: Option<usize> = None;
+            for (idx, (item, &depth)) in self.items.iter().zip(&self.depths).enumerate() {
+                if folded_depth.is_some_and(|d| depth > d) {
+                    continue;
+                }
+                folded_depth = self
+                    .collapsed
+                    .contains(&item.0.commitment.id)
+                    .then_some(depth);
+                self.visible.push(idx);
+            }
+            self.matches = (0..self.visible.len()).collect();
+        }
+
+        if let Some(i) = self.get_selected_index()
+            && i >= self.visible.len()
+        {
+            self.list_state.select(self.visible.len().checked_sub(1));
+        }
+    }
+
+    /// Rebuild the list after the search or filter changed, keeping the selected commitment
+    /// selected while it's still shown and otherwise moving to the first match
+    fn refilter(&mut self) {
+        let selected_id = self.get_selected_item().map(|c| c.0.commitment.id);
+        self.rebuild_visible();
+
+        let row = selected_id
+            .and_then(|id| {
+                self.visible
+                    .iter()
+                    .position(|&idx| self.items[idx].0.commitment.id == id)
+            })
+            .or_else(|| self.matches.first().copied())
+            .or((!self.visible.is_empty()).then_some(0));
+        if row != self.get_selected_index() {
+            self.chart_offset = 0;
+        }
+        self.list_state.select(row);
+    }
+
+    /// Start typing a new search
+    pub fn start_search(&mut self) {
+        self.search.clear();
+        self.refilter();
+        self.switch_input_mode(InputMode::Search);
+    }
+
+    /// Change the search and filter the list by it right away
+    pub fn edit_search(&mut self, edit: impl FnOnce(&mut String)) {
+        edit(&mut self.search);
+        self.refilter();
+        self.set_message(format!("{} match(es)", self.matches.len()));
+    }
+
+    /// Show only the commitments of `filter`, or everything again when it's already on
+    pub fn toggle_filter(&mut self, filter: ListFilter) {
+        self.filter = if self.filter == filter {
+            ListFilter::All
+        } else {
+            filter
+        };
+        self.refilter();
+        self.set_message(match self.filter {
+            ListFilter::All => "Showing all commitments".to_string(),
+            filter => format!("Showing {filter} commitments ({})", self.matches.len()),
+        });
+    }
+
+    /// Drop the search and the filter
+    pub fn clear_filter(&mut self) {
+        if !self.is_filtering() {
+            return;
+        }
+        self.search.clear();
+        self.filter = ListFilter::All;
+        self.refilter();
+        self.set_message("Showing all commitments");
+    }
+
+    /// Select the match `times` matches after the selected row, wrapping around
+    pub fn next_match(&mut self, times: usize) {
+        for _ in 0..times {
+            let current = self.get_selected_index();
+            let next = self
+                .matches
+                .iter()
+                .copied()
+                .find(|&row| current.is_none_or(|c| row > c))
+                .or_else(|| self.matches.first().copied());
+            self.select_match(next);
+        }
+    }
+
+    /// Select the match `times` matches before the selected row, wrapping around
+    pub fn previous_match(&mut self, times: usize) {
+        for _ in 0..times {
+            let current = self.get_selected_index();
+            let previous = self
+                .matches
+                .iter()
+                .rev()
+                .copied()
+                .find(|&row| current.is_none_or(|c| row < c))
+                .or_else(|| self.matches.last().copied());
+            self.select_match(previous);
+        }
+    }
+
+    fn select_match(&mut self, row: Option<usize>) {
+        match row {
+            Some(row) => {
+                self.list_state.select(Some(row));
+                self.chart_offset = 0;
+            }
+            None => self.set_message("No matches"),
+        }
+    }
+
+    /// Fold or unfold the sub-commitments of the selected commitment.
+    pub fn toggle_fold_selected(&mut self) {
+        let Some(id) = self.get_selected_item().map(|c| c.0.commitment.id) else {
+            return;
+        };
+        if !self.has_children(id) {
+            return;
+        }
+
+        if !self.collapsed.remove(&id) {
+            self.collapsed.insert(id);
+        }
+        self.rebuild_visible();
     }
 
     /// Get all the items
@@ -149,14 +530,15 @@ impl App {
 
     pub fn next(&mut self) {
         let i = match self.get_selected_index() {
-            Some(i) if !self.items.is_empty() => (i + 1) % self.items.len(),
+            Some(i) if !self.visible.is_empty() => (i + 1) % self.visible.len(),
             _ => 0,
         };
         self.list_state.select(Some(i));
+        self.chart_offset = 0;
     }
 
     pub fn previous(&mut self) {
-        let len = self.items.len();
+        let len = self.visible.len();
         if len == 0 {
             self.list_state.select(None);
             return;
@@ -166,53 +548,285 @@ impl App {
             Some(i) => i - 1,
         };
         self.list_state.select(Some(i));
+        self.chart_offset = 0;
     }
 
     pub fn jump_first(&mut self) {
-        if !self.items.is_empty() {
+        if !self.visible.is_empty() {
             self.list_state.select(Some(0));
+            self.chart_offset = 0;
         }
     }
 
     pub fn jump_last(&mut self) {
-        if !self.items.is_empty() {
-            self.list_state.select(Some(self.items.len() - 1));
+        if !self.visible.is_empty() {
+            self.list_state.select(Some(self.visible.len() - 1));
+            self.chart_offset = 0;
+        }
+    }
+
+    /// Select the `row`-th row of the list, counting from 1, or the last one when there are fewer
+    pub fn jump_to_row(&mut self, row: usize) {
+        if !self.visible.is_empty() {
+            let i = row.saturating_sub(1).min(self.visible.len() - 1);
+            self.list_state.select(Some(i));
+            self.chart_offset = 0;
+        }
+    }
+
+    /// Forget the click targets of the previous frame, before drawing the next one
+    pub fn clear_click_targets(&mut self) {
+        self.click_targets.clear();
+    }
+
+    pub fn add_click_target(&mut self, area: Rect, target: ClickTarget) {
+        self.click_targets.push((area, target));
+    }
+
+    /// The topmost click target at a cell of the screen
+    pub fn click_target_at(&self, column: u16, row: u16) -> Option<ClickTarget> {
+        self.click_targets
+            .iter()
+            .rev()
+            .find(|(area, _)| {
+                (area.left()..area.right()).contains(&column)
+                    && (area.top()..area.bottom()).contains(&row)
+            })
+            .map(|(_, target)| *target)
+    }
+
+    /// Show the logs of the selected commitment and its sub-commitments in the week of `week`
+    pub async fn show_week_logs(&mut self, week: NaiveDate) -> anyhow::Result<()> {
+        let Some(sel) = self.get_selected_item() else {
+            return Ok(());
+        };
+        let (id, name, unit) = (
+            sel.0.commitment.id,
+            sel.0.commitment.name.clone(),
+            sel.0.commitment.unit(),
+        );
+
+        let ids = descendant_ids(&self.pool, id).await?;
+        let logs: Vec<ProgressLog> = logs_for_week(&self.pool, week)
+            .await?
+            .into_iter()
+            .filter(|log| ids.contains(&log.commitment_id))
+            .collect();
+        self.set_message(format!(
+            "{} log(s) from {week} to {}",
+            logs.len(),
+            week + Duration::days(6)
+        ));
+        self.switch_input_mode(InputMode::WeekLogs(WeekLogs {
+            name,
+            unit,
+            week,
+            logs,
+        }));
+        Ok(())
+    }
+
+    /// Scroll the weekly chart towards older weeks, until the oldest week is in view.
+    pub fn scroll_chart_older(&mut self, weeks: usize) {
+        let max_offset = self
+            .get_selected_item()
+            .map(|sel| {
+                weeks_since(&sel.0.commitment.goal())
+                    .len()
+                    .saturating_sub(self.chart_capacity)
+            })
+            .unwrap_or(0);
+        self.chart_offset = (self.chart_offset + weeks).min(max_offset);
+    }
+
+    /// Scroll the weekly chart towards the current week.
+    pub fn scroll_chart_newer(&mut self, weeks: usize) {
+        self.chart_offset = self.chart_offset.saturating_sub(weeks);
+    }
+
+    /// Widen the bars of the weekly chart, showing fewer weeks at once.
+    pub fn zoom_chart_in(&mut self) {
+        self.chart_bar_width = (self.chart_bar_width + 1).min(MAX_CHART_BAR_WIDTH);
+    }
+
+    /// Narrow the bars of the weekly chart, showing more weeks at once.
+    pub fn zoom_chart_out(&mut self) {
+        self.chart_bar_width = self
+            .chart_bar_width
+            .saturating_sub(1)
+            .max(MIN_CHART_BAR_WIDTH);
+    }
+
+    /// Switch between the weekly bar chart and the cumulative line chart.
+    pub fn toggle_detail_view(&mut self) {
+        self.detail_view = match self.detail_view {
+            DetailView::Weekly => DetailView::Cumulative,
+            DetailView::Cumulative => DetailView::Weekly,
+        };
+    }
+
+    /// Reactivate the selected commitment, starting over from the current week with `fresh`
+    pub async fn reactivate_selected(&mut self, fresh: bool) -> anyhow::Result<()> {
+        if let Some(sel) = self.get_selected_item()
+            && let Some(end) = sel.0.commitment.end_monday
+            && end < get_monday_this_week()
+        {
+            self.set_message(format!(
+                "#{} ended in the week of {end}",
+                sel.0.commitment.id
+            ));
+            return Ok(());
+        }
+
+        if let Some(sel) = self.get_selected_item()
+            && !sel.0.commitment.active
+        {
+            crate::db::reactivate_commiment(&self.pool, sel.0.commitment.id, fresh).await?;
+            self.set_message(if fresh {
+                format!("Reactivated #{} from a fresh start", sel.0.commitment.id)
+            } else {
+                format!("Reactivated #{}", sel.0.commitment.id)
+            });
+            self.refresh_from_db().await?;
+        };
+        Ok(())
+    }
+
+    /// Archive the active commitments among the `count` rows from the selected one on
+    pub async fn archive_selected(&mut self, count: usize) -> anyhow::Result<()> {
+        let Some(start) = self.get_selected_index() else {
+            return Ok(());
+        };
+        let ids: Vec<i64> = self
+            .visible
+            .iter()
+            .skip(start)
+            .take(count.max(1))
+            .map(|&idx| &self.items[idx].0)
+            .filter(|c| c.commitment.active)
+            .map(|c| c.commitment.id)
+            .collect();
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        crate::db::archive_commitments(&self.pool, &ids).await?;
+        let archived = ids
+            .iter()
+            .map(|id| format!("#{id}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.set_message(format!("Archived {archived}"));
+        self.refresh_from_db().await?;
+        Ok(())
+    }
+
+    pub fn get_presets(&self) -> &[(String, Preset)] {
+        &self.presets
+    }
+
+    /// Open the preset picker, unless there are no presets to pick from
+    pub fn open_preset_picker(&mut self) {
+        if self.presets.is_empty() {
+            self.set_message("No presets yet, add them as [presets.<name>] to the config file");
+            return;
         }
+        self.preset_state.select(Some(0));
+        self.switch_input_mode(InputMode::PickPreset);
     }
 
-    pub async fn reactivate_selected(&mut self) -> anyhow::Result<()> {
-        if let Some(sel) = self.get_selected_item() {
-            if !sel.0.active {
-                crate::db::reactivate_commiment(&self.pool, sel.0.id).await?;
-                self.set_message(format!("Reactivated #{}", sel.0.id));
-                self.refresh_from_db().await?;
-            };
+    pub fn next_preset(&mut self) {
+        let i = self.preset_state.selected().map_or(0, |i| i + 1);
+        self.preset_state
+            .select(Some(i % self.presets.len().max(1)));
+    }
+
+    pub fn previous_preset(&mut self) {
+        let len = self.presets.len().max(1);
+        let i = self.preset_state.selected().map_or(0, |i| i + len - 1);
+        self.preset_state.select(Some(i % len));
+    }
+
+    /// Log the preset at `idx` and go back to normal mode
+    pub async fn log_preset(&mut self, idx: usize) -> anyhow::Result<()> {
+        let Some((name, preset)) = self.presets.get(idx).cloned() else {
+            return Ok(());
         };
+
+        let (c, amount) = match preset.resolve(&self.pool).await {
+            Ok(resolved) => resolved,
+            Err(PresetError::Database(e)) => return Err(e.into()),
+            Err(e) => {
+                self.set_message(format!("Cannot log @{name}: {e}"));
+                return Ok(());
+            }
+        };
+
+        crate::db::log_record_id(&self.pool, c.id, amount, preset.note.as_deref()).await?;
+        self.set_message(format!(
+            "Logged {} to '{}' with @{name}",
+            c.unit().format(amount),
+            c.name
+        ));
+        self.refresh_from_db().await?;
+        self.switch_input_mode(InputMode::Normal);
         Ok(())
     }
 
-    pub async fn archive_selected(&mut self) -> anyhow::Result<()> {
-        if let Some(sel) = self.get_selected_item() {
-            if sel.0.active {
-                crate::db::archive_commiment(&self.pool, sel.0.id).await?;
-                self.set_message(format!("Archived #{}", sel.0.id));
-                self.refresh_from_db().await?;
-            };
+    /// Ask for confirmation before deleting the selected commitment
+    pub async fn request_delete_selected(&mut self) -> anyhow::Result<()> {
+        let Some(sel) = self.get_selected_item() else {
+            return Ok(());
         };
+        if let Some(preview) = crate::db::delete_preview(&self.pool, sel.0.commitment.id).await? {
+            self.set_message(format!(
+                "Delete '{}' (#{}) and its {} log(s)?",
+                preview.name, preview.id, preview.logs
+            ));
+            self.switch_input_mode(InputMode::ConfirmDelete(preview));
+        }
         Ok(())
     }
 
+    /// Delete the commitment waiting for confirmation and go back to normal mode
+    pub async fn confirm_delete(&mut self) -> anyhow::Result<()> {
+        if let InputMode::ConfirmDelete(preview) = &self.input_mode {
+            let id = preview.id;
+            crate::db::delete_commitment(&self.pool, id).await?;
+            self.set_message(format!("Deleted #{id}"));
+            self.collapsed.remove(&id);
+            self.refresh_from_db().await?;
+        }
+        self.switch_input_mode(InputMode::Normal);
+        Ok(())
+    }
+
+    /// Add a key press in normal mode, returning the action and its count once there is one
+    pub fn press_key(&mut self, key: KeyEvent) -> Option<(Action, Option<usize>)> {
+        self.pending_keys.press(&self.keymap, key)
+    }
+
+    pub fn open_help(&mut self) {
+        self.help_scroll = 0;
+        self.switch_input_mode(InputMode::Help);
+    }
+
     /// This set the generat input guidance.
-    pub fn get_input_help_msg(state: &InputMode) -> &str {
+    pub fn get_input_help_msg(&self, state: &InputMode) -> String {
         match state {
-            InputMode::Normal => NORMAL_MODE_KEY_MAPS_HINT,
-            InputMode::LogHours => LOG_MODE_KEY_MAPS_HINT,
-            InputMode::CreateCommitment(_) => CREATE_MODE_COMMITMENT_KEY_MAPS_HINT,
+            InputMode::Normal => self.keymap.footer_hint(),
+            InputMode::LogHours => LOG_MODE_KEY_MAPS_HINT.to_string(),
+            InputMode::CreateCommitment(_) => CREATE_MODE_COMMITMENT_KEY_MAPS_HINT.to_string(),
+            InputMode::ConfirmDelete(_) => CONFIRM_DELETE_MODE_KEY_MAPS_HINT.to_string(),
+            InputMode::PickPreset => PICK_PRESET_MODE_KEY_MAPS_HINT.to_string(),
+            InputMode::Help => HELP_MODE_KEY_MAPS_HINT.to_string(),
+            InputMode::WeekLogs(_) => WEEK_LOGS_MODE_KEY_MAPS_HINT.to_string(),
+            InputMode::Search => SEARCH_MODE_KEY_MAPS_HINT.to_string(),
         }
     }
 
     pub fn switch_input_mode(&mut self, target_state: InputMode) {
-        self.keymap_msg = App::get_input_help_msg(&target_state).to_string();
+        self.keymap_msg = self.get_input_help_msg(&target_state);
         match target_state {
             InputMode::LogHours => {
                 self.input_buffer = String::new();
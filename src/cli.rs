@@ -1,28 +1,207 @@
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
 use clap::{Parser, Subcommand};
 
+use crate::{
+    completions::{COMPLETE_COMMITMENTS_COMMAND, CompletionShell},
+    model::CarryOver,
+    report::ReportFormat,
+    selector::Selector,
+    stats::DEFAULT_RECENT_WEEKS,
+    unit::Unit,
+};
+
 /// Weekly commitment tracker
 #[derive(Parser)]
 #[command(
     name = "Weekly Progress Tracker",
     about = "Track weekly commitments and hours",
     version = "0.0.1",
-    author = "Isaac Leong"
+    author = "Isaac Leong",
+    after_help = "Commitments can be given by id (`3` or `#3`), by name, or by the start of a name."
 )]
 #[derive(Debug)]
 pub struct Cli {
+    /// Read this config file instead of `$XDG_CONFIG_HOME/weekly-tracker/config.toml`
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+    /// Use this database file, overriding `database.path` from the config
+    #[arg(long, global = true)]
+    pub db: Option<PathBuf>,
+    /// Start weeks on this day, overriding `week.start` from the config
+    #[arg(long, global = true, value_name = "DAY")]
+    pub week_start: Option<String>,
+    /// Use this TUI theme, overriding `tui.theme` from the config
+    #[arg(long, global = true, value_name = "NAME")]
+    pub theme: Option<String>,
+    /// Redraw the TUI every this many milliseconds, overriding `tui.tick_ms` from the config
+    #[arg(long, global = true, value_name = "MS")]
+    pub tick_ms: Option<u64>,
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum Commands {
-    Add { name: String, weekly_hours: f64 },
-    Archive { id: i64 },
-    Reactivate { id: i64 },
+    /// Add a commitment, prompting for the name, target and start week when they are left out
+    Add {
+        name: Option<String>,
+        /// Weekly target in the commitment's unit, e.g. `5`, `1h30m` or `20km`
+        target: Option<String>,
+        /// Treat the weekly target as a limit to stay under instead of a target to reach
+        #[arg(long)]
+        limit: bool,
+        /// hours, minutes, count, km, or any other label such as `pages`
+        #[arg(long, default_value = "hours")]
+        unit: Unit,
+        /// Also require showing up on at least this many different days every week
+        #[arg(long, conflicts_with = "limit", value_parser = clap::value_parser!(i64).range(1..=7))]
+        sessions: Option<i64>,
+        /// What happens to past weeks' surplus or debt: none, full, cap:<amount> or decay:<percent>
+        #[arg(long, default_value = "full")]
+        carry_over: CarryOver,
+        /// Make this a sub-commitment of another one, its logs then count toward the parent
+        #[arg(long)]
+        parent: Option<Selector>,
+        /// Any day of the first week of the commitment, defaults to the next week
+        #[arg(long)]
+        start: Option<NaiveDate>,
+        /// Any day of the last week of the commitment, it is archived once that week is over
+        #[arg(long)]
+        end: Option<NaiveDate>,
+    },
+    /// Set the last week of a commitment, or remove its end when no date is given
+    SetEnd {
+        commitment: Selector,
+        /// Any day of the last week
+        end: Option<NaiveDate>,
+    },
+    /// Move a commitment under another one, or back to the top level when no parent is given
+    SetParent {
+        commitment: Selector,
+        parent: Option<Selector>,
+    },
+    /// Change how a commitment carries surplus or debt over to the next weeks
+    CarryOver {
+        commitment: Selector,
+        /// none, full, cap:<amount> or decay:<percent>
+        policy: CarryOver,
+    },
+    Archive {
+        commitment: Selector,
+    },
+    /// Permanently remove a commitment and all of its logs
+    Delete {
+        commitment: Selector,
+        /// Only show what would be deleted
+        #[arg(long)]
+        dry_run: bool,
+        /// Delete without asking first, needed when not run in a terminal
+        #[arg(long, short, conflicts_with = "dry_run")]
+        yes: bool,
+    },
+    Reactivate {
+        commitment: Selector,
+        /// Start over from the current week, dropping the debt or surplus built up before
+        #[arg(long)]
+        fresh: bool,
+    },
     List,
-    Log { name: String, hours: f32 },
-    LogID { id: i64, hours: f32 },
-    TrackID { id: i64 },
+    /// Log progress for today, picking the commitment and amount interactively when left out
+    #[command(alias = "log-id")]
+    Log {
+        /// A commitment, or `@name` for a preset from the config file
+        commitment: Option<Selector>,
+        /// Amount in the commitment's unit, e.g. `1.5`, `45m` or `20 pages`
+        amount: Option<String>,
+        /// Short note kept with the log, shown in weekly reports
+        #[arg(long)]
+        note: Option<String>,
+    },
+    /// Show the current week's progress of a commitment
+    #[command(alias = "track-id")]
+    Track {
+        commitment: Selector,
+    },
     TrackAll,
-    History { id: i64 },
+    History {
+        commitment: Selector,
+        /// Print the cumulative done and required hours for every week
+        #[arg(long)]
+        cumulative: bool,
+    },
+    Stats {
+        commitment: Selector,
+        /// Number of recent weeks used for the hit rate
+        #[arg(long, default_value_t = DEFAULT_RECENT_WEEKS)]
+        weeks: usize,
+    },
+    /// Set a total to reach by a due date, e.g. `milestone 3 60 2027-03-01`
+    Milestone {
+        commitment: Selector,
+        /// Total in the commitment's unit
+        #[arg(required_unless_present = "clear")]
+        total: Option<String>,
+        #[arg(required_unless_present = "clear")]
+        due: Option<NaiveDate>,
+        /// Remove the milestone
+        #[arg(long, conflicts_with_all = ["total", "due"])]
+        clear: bool,
+    },
+    /// List the milestones of active commitments with the pace they need
+    Milestones,
+    /// Spread a commitment's weekly target across the days of a week
+    Plan {
+        commitment: Selector,
+        /// Amounts for each day from the first day of the week, e.g. `1h 0 1h 0 1h 0 0`, shows the plan when left out
+        #[arg(num_args = 0..=7)]
+        days: Vec<String>,
+        /// Any day of the week to plan, defaults to the current week
+        #[arg(long)]
+        week: Option<NaiveDate>,
+        /// Go back to an even split for the week
+        #[arg(long, conflicts_with = "days")]
+        reset: bool,
+    },
+    /// Show what is planned and done today for every active commitment
+    Today,
+    /// Generate a review of a week's progress
+    Report {
+        /// Any day of the week to report on, defaults to the current week
+        #[arg(long)]
+        week: Option<NaiveDate>,
+        #[arg(long, value_enum, default_value_t = ReportFormat::Md)]
+        format: ReportFormat,
+    },
+    /// List the quick-log presets from the config file
+    Presets,
+    /// Show or create the config file
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Print a shell completion script, e.g. `source <(rust-log-book completions bash)`
+    Completions {
+        shell: CompletionShell,
+    },
+    /// Names and ids of active commitments, used by the completion scripts
+    #[command(name = COMPLETE_COMMITMENTS_COMMAND, hide = true)]
+    CompleteCommitments {
+        shell: CompletionShell,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Print the settings in effect, defaults included
+    Show,
+    /// Print where the config file is read from
+    Path,
+    /// Write a commented config file with the defaults
+    Init {
+        /// Overwrite an existing config file
+        #[arg(long)]
+        force: bool,
+    },
 }
@@ -0,0 +1,229 @@
+use std::io;
+
+use clap::{CommandFactory, ValueEnum};
+use clap_complete::{Shell, generate};
+use sqlx::SqlitePool;
+
+use crate::{cli::Cli, db::list_all_commitments, util::get_monday_this_week};
+
+/// Name of the hidden subcommand the completion scripts call for commitment names
+pub const COMPLETE_COMMITMENTS_COMMAND: &str = "__complete-commitments";
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl CompletionShell {
+    fn shell(self) -> Shell {
+        match self {
+            CompletionShell::Bash => Shell::Bash,
+            CompletionShell::Zsh => Shell::Zsh,
+            CompletionShell::Fish => Shell::Fish,
+        }
+    }
+}
+
+/// Subcommands, aliases included, whose first argument is a commitment selector
+fn commitment_subcommands() -> Vec<String> {
+    Cli::command()
+        .get_subcommands()
+        .filter(|cmd| {
+            cmd.get_positionals()
+                .next()
+                .is_some_and(|arg| arg.get_id() == "commitment")
+        })
+        .flat_map(|cmd| {
+            std::iter::once(cmd.get_name().to_string())
+                .chain(cmd.get_all_aliases().map(str::to_string))
+        })
+        .collect()
+}
+
+/// Options that take the next word as their value, such as `--db <path>`, which the hooks skip
+/// to find the subcommand and its first argument wherever the options are. With `global_only`,
+/// just the ones of every subcommand, which the hooks pass on when asking for the names.
+fn options_with_values(global_only: bool) -> Vec<String> {
+    let cmd = Cli::command();
+    let mut options: Vec<String> = std::iter::once(&cmd)
+        .chain(cmd.get_subcommands())
+        .flat_map(|cmd| cmd.get_arguments())
+        .filter(|arg| !arg.is_positional() && arg.get_action().takes_values())
+        .filter(|arg| !global_only || arg.is_global_set())
+        .flat_map(|arg| {
+            arg.get_long()
+                .map(|long| format!("--{long}"))
+                .into_iter()
+                .chain(arg.get_short().map(|short| format!("-{short}")))
+        })
+        .collect();
+    options.sort();
+    options.dedup();
+    options
+}
+
+/// Print the completion script for `shell`.
+///
+/// The script clap generates is followed by a hook that asks the database for the names and
+/// ids of active commitments when completing the first argument of the subcommands taking one.
+pub fn print_completions(shell: CompletionShell) {
+    let bin = env!("CARGO_BIN_NAME");
+    let mut cmd = Cli::command();
+    generate(shell.shell(), &mut cmd, bin, &mut io::stdout());
+
+    let subcommands = commitment_subcommands();
+    let options = options_with_values(false);
+    let global_options = options_with_values(true);
+    let hook = match shell {
+        CompletionShell::Bash => format!(
+            r#"
+_{fn_name}_commitments() {{
+    local i word subcommand="" positionals=0 globals=()
+    for (( i = 1; i < COMP_CWORD; i++ )); do
+        word="${{COMP_WORDS[i]}}"
+        case "$word" in
+            {options})
+                # bash splits `--db=path` into three words
+                if [[ "${{COMP_WORDS[i+1]}}" == "=" ]]; then (( i += 2 )); else (( i++ )); fi
+                case "$word" in
+                    {global_options}) globals+=("$word" "${{COMP_WORDS[i]}}") ;;
+                esac
+                ;;
+            -*) ;;
+            *)
+                if [[ -z "$subcommand" ]]; then subcommand="$word"; else (( positionals++ )); fi
+                ;;
+        esac
+    done
+    if [[ $positionals -eq 0 && "${{COMP_WORDS[COMP_CWORD]}}" != -* ]]; then
+        case "$subcommand" in
+            {cases})
+                local IFS=$'\n'
+                COMPREPLY=($(compgen -W "$({bin} "${{globals[@]}}" {hook} bash 2>/dev/null)" -- "${{COMP_WORDS[COMP_CWORD]}}"))
+                return 0
+                ;;
+        esac
+    fi
+    _{bin} "$@"
+}}
+complete -F _{fn_name}_commitments -o nosort -o bashdefault -o default {bin}
+"#,
+            fn_name = bin.replace('-', "_"),
+            options = options.join("|"),
+            global_options = global_options.join("|"),
+            cases = subcommands.join("|"),
+            hook = COMPLETE_COMMITMENTS_COMMAND,
+        ),
+        CompletionShell::Zsh => format!(
+            r#"
+_{fn_name}_commitments() {{
+    local i word subcommand="" positionals=0
+    local -a globals
+    for (( i = 2; i < CURRENT; i++ )); do
+        word=${{words[i]}}
+        case $word in
+            ({global_options}) globals+=($word ${{words[i+1]}}); (( i++ )) ;;
+            ({options}) (( i++ )) ;;
+            (-*) ;;
+            (*)
+                if [[ -z $subcommand ]]; then subcommand=$word; else (( positionals++ )); fi
+                ;;
+        esac
+    done
+    if (( positionals == 0 )) && [[ ${{words[CURRENT]}} != -* ]]; then
+        case $subcommand in
+            ({cases})
+                local -a candidates
+                candidates=("${{(@f)$({bin} $globals {hook} zsh 2>/dev/null)}}")
+                _describe -t commitments 'commitment' candidates
+                return
+                ;;
+        esac
+    fi
+    _{bin} "$@"
+}}
+compdef _{fn_name}_commitments {bin}
+"#,
+            fn_name = bin.replace('-', "_"),
+            options = options.join("|"),
+            global_options = global_options.join("|"),
+            cases = subcommands.join("|"),
+            hook = COMPLETE_COMMITMENTS_COMMAND,
+        ),
+        CompletionShell::Fish => format!(
+            r#"
+function __{fn_name}_completing_commitment
+    set -l subcommand
+    set -l positionals 0
+    set -l skip 0
+    for word in (commandline -opc)[2..-1]
+        if test $skip -eq 1
+            set skip 0
+            continue
+        end
+        switch $word
+            case {options}
+                set skip 1
+            case '-*'
+            case '*'
+                if test -z "$subcommand"
+                    set subcommand $word
+                else
+                    set positionals (math $positionals + 1)
+                end
+        end
+    end
+    test $positionals -eq 0; and contains -- "$subcommand" {cases}
+end
+function __{fn_name}_commitments
+    set -l globals
+    set -l words (commandline -opc)
+    for i in (seq 2 (math (count $words) - 1))
+        if contains -- $words[$i] {global_options}
+            set -a globals $words[$i] $words[(math $i + 1)]
+        end
+    end
+    {bin} $globals {hook} fish 2>/dev/null
+end
+complete -c {bin} -n __{fn_name}_completing_commitment -f -a "(__{fn_name}_commitments)"
+"#,
+            fn_name = bin.replace('-', "_"),
+            options = options.join(" "),
+            global_options = global_options.join(" "),
+            cases = subcommands.join(" "),
+            hook = COMPLETE_COMMITMENTS_COMMAND,
+        ),
+    };
+    print!("{hook}");
+}
+
+/// Print the names and ids of active commitments, one candidate per line, for the hooks above.
+///
+/// Ended commitments are left out even before the archive pass of a regular command catches up.
+pub async fn print_commitment_candidates(
+    pool: &SqlitePool,
+    shell: CompletionShell,
+) -> Result<(), sqlx::Error> {
+    let this_monday = get_monday_this_week();
+    let mut commitments = list_all_commitments(pool).await?;
+    commitments.retain(|c| c.active && c.end_monday.is_none_or(|end| end >= this_monday));
+
+    let candidates = commitments
+        .iter()
+        .map(|c| (c.name.clone(), format!("#{}", c.id)))
+        .chain(
+            commitments
+                .iter()
+                .map(|c| (c.id.to_string(), c.name.clone())),
+        );
+    for (value, description) in candidates {
+        match shell {
+            CompletionShell::Bash => println!("{value}"),
+            CompletionShell::Zsh => println!("{}:{description}", value.replace(':', "\\:")),
+            CompletionShell::Fish => println!("{value}\t{description}"),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,360 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, bail};
+use chrono::{NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+use crate::{
+    keymap::{Action, KeyBinding, Keymap},
+    model::Commitment,
+    selector::{SelectError, Selector, select_commitment},
+    theme::{CustomTheme, Theme, no_color},
+    util::get_monday_this_week,
+};
+
+/// Written by `config init`, every setting is commented out so the defaults apply until changed
+const CONFIG_TEMPLATE: &str = r##"# Weekly tracker configuration
+
+[database]
+# SQLite file, relative paths start from the current directory
+# path = "./data/weekly_commit.db"
+
+[progress]
+# Percent of the weekly target below which progress is shown as behind...
+# low = 25.0
+# ...and from which it is shown as on track. Limits show a warning from this percent on.
+# high = 75.0
+
+[week]
+# The day weeks start on. Plans already made for a week stay with the day it started on before.
+# start = "monday"
+
+[tui]
+# Milliseconds between redraws of the TUI
+# tick_ms = 20
+# dark, light, high-contrast or one of the [themes.<name>] below. NO_COLOR turns colours off.
+# theme = "dark"
+
+# Own themes start from a built-in one and change some of its colours,
+# given as names such as "red" and "lightblue" or as "#rrggbb"
+# [themes.mine]
+# base = "light"
+# good = "#2e7d32"
+# bad = "red"
+
+# Keys of the TUI by action, replacing the default keys of that action. Keys can have
+# ctrl-, alt- and shift- in front and follow each other, as in "gg" or "ctrl-x d".
+# Press ? in the TUI for every action and its keys.
+# [keys]
+# down = ["j", "down"]
+# first = "gg"
+
+# Quick logs, used as `log @gym` or with `p` in the TUI
+# [presets.gym]
+# commitment = "Exercise"
+# amount = "1h"
+# note = "Gym session"
+"##;
+
+/// `$XDG_CONFIG_HOME/weekly-tracker/config.toml`, falling back to `~/.config`
+pub fn config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("weekly-tracker").join("config.toml"))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub path: PathBuf,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("./data/weekly_commit.db"),
+        }
+    }
+}
+
+/// Where progress turns from behind to on track, in percent of the weekly target
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProgressConfig {
+    pub low: f64,
+    pub high: f64,
+}
+
+impl Default for ProgressConfig {
+    fn default() -> Self {
+        Self {
+            low: 25.0,
+            high: 75.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WeekConfig {
+    /// A day such as `monday` or `sunday`
+    pub start: String,
+}
+
+impl Default for WeekConfig {
+    fn default() -> Self {
+        Self {
+            start: "monday".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TuiConfig {
+    pub tick_ms: u64,
+    /// A built-in theme or one defined under `[themes]`
+    pub theme: String,
+}
+
+impl Default for TuiConfig {
+    fn default() -> Self {
+        Self {
+            tick_ms: 20,
+            theme: "dark".to_string(),
+        }
+    }
+}
+
+/// A quick log such as `log @gym`, defined in the config file:
+///
+/// ```toml
+/// [presets.gym]
+/// commitment = "Exercise"
+/// amount = "1h"
+/// note = "Gym session"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Preset {
+    /// Id or name of the commitment, the same as on the command line
+    pub commitment: String,
+    /// Amount in the commitment's unit, e.g. `15m` or `20 pages`
+    pub amount: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Why a preset cannot be logged
+#[derive(Debug)]
+pub enum PresetError {
+    /// The commitment of the preset is not a valid selector
+    Selector(String),
+    Select(SelectError),
+    /// The commitment was archived
+    Archived(String),
+    /// The end week of the commitment is over
+    Ended {
+        name: String,
+        end: NaiveDate,
+    },
+    /// The amount does not fit the unit of the commitment
+    Amount {
+        amount: String,
+        reason: String,
+    },
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for PresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PresetError::Selector(reason) => write!(f, "{reason}"),
+            PresetError::Select(e) => write!(f, "{e}"),
+            PresetError::Archived(name) => write!(f, "'{name}' is archived"),
+            PresetError::Ended { name, end } => write!(f, "'{name}' ended in the week of {end}"),
+            PresetError::Amount { amount, reason } => {
+                write!(f, "invalid amount '{amount}': {reason}")
+            }
+            PresetError::Database(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for PresetError {}
+
+impl From<sqlx::Error> for PresetError {
+    fn from(e: sqlx::Error) -> Self {
+        PresetError::Database(e)
+    }
+}
+
+impl Preset {
+    /// The commitment this preset logs to and the amount it logs
+    pub async fn resolve(&self, pool: &SqlitePool) -> Result<(Commitment, f64), PresetError> {
+        let selector = self
+            .commitment
+            .parse::<Selector>()
+            .map_err(PresetError::Selector)?;
+        let c = select_commitment(pool, &selector)
+            .await?
+            .map_err(PresetError::Select)?;
+        if !c.active {
+            return Err(PresetError::Archived(c.name));
+        }
+        if let Some(end) = c.end_monday
+            && end < get_monday_this_week()
+        {
+            return Err(PresetError::Ended { name: c.name, end });
+        }
+
+        let amount = c
+            .unit()
+            .parse_amount(&self.amount)
+            .map_err(|reason| PresetError::Amount {
+                amount: self.amount.clone(),
+                reason,
+            })?;
+        Ok((c, amount))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub database: DatabaseConfig,
+    pub progress: ProgressConfig,
+    pub week: WeekConfig,
+    pub tui: TuiConfig,
+    /// Quick logs by name, used as `@name`
+    pub presets: BTreeMap<String, Preset>,
+    /// Own themes by name, picked with `tui.theme`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub themes: BTreeMap<String, CustomTheme>,
+    /// Keys of the TUI, by the action they are bound to
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub keys: BTreeMap<Action, KeyBinding>,
+}
+
+impl Config {
+    /// Read the config file at `path`, or at the default location when it's `None`.
+    ///
+    /// A missing default file means the defaults, a missing file that was asked for is an error.
+    pub fn load(path: Option<&Path>) -> anyhow::Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match config_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(Config::default()),
+            },
+        };
+
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("cannot read {}", path.display()))?;
+        let config: Config = toml::from_str(&text)
+            .with_context(|| format!("invalid config in {}", path.display()))?;
+        config
+            .validate()
+            .with_context(|| format!("invalid config in {}", path.display()))?;
+        Ok(config)
+    }
+
+    /// Check the values that parse fine but make no sense, also after overriding them from the
+    /// command line
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.database.path.as_os_str().is_empty() {
+            bail!("database.path cannot be empty");
+        }
+
+        let ProgressConfig { low, high } = self.progress;
+        if !(0.0..=100.0).contains(&low) || !(0.0..=100.0).contains(&high) {
+            bail!("progress.low and progress.high are percentages between 0 and 100");
+        }
+        if low >= high {
+            bail!("progress.low ({low}) has to be below progress.high ({high})");
+        }
+
+        if self.week.start.parse::<Weekday>().is_err() {
+            bail!(
+                "week.start has to be a day such as monday or sunday, got '{}'",
+                self.week.start
+            );
+        }
+
+        if !(1..=1000).contains(&self.tui.tick_ms) {
+            bail!(
+                "tui.tick_ms has to be between 1 and 1000, got {}",
+                self.tui.tick_ms
+            );
+        }
+
+        for (name, theme) in &self.themes {
+            theme.build().with_context(|| format!("in themes.{name}"))?;
+        }
+        Theme::named(&self.tui.theme, &self.themes).context("in tui.theme")?;
+        Keymap::new(&self.keys)?;
+
+        for (name, preset) in &self.presets {
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                bail!("preset names cannot be empty or contain spaces, got '{name}'");
+            }
+            if preset.commitment.trim().is_empty() || preset.amount.trim().is_empty() {
+                bail!("presets.{name} needs both a commitment and an amount");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The day weeks start on
+    pub fn week_start(&self) -> Weekday {
+        self.week
+            .start
+            .parse()
+            .expect("Week start was validated on load")
+    }
+
+    pub fn preset(&self, name: &str) -> Option<&Preset> {
+        self.presets.get(name)
+    }
+
+    /// The colours of the TUI, none at all when `NO_COLOR` is set
+    pub fn theme(&self) -> Theme {
+        if no_color() {
+            return Theme::monochrome();
+        }
+        Theme::named(&self.tui.theme, &self.themes).expect("Theme was validated on load")
+    }
+
+    /// The keys of the TUI, the defaults with the ones from `[keys]` in their place
+    pub fn keymap(&self) -> Keymap {
+        Keymap::new(&self.keys).expect("Keys were validated on load")
+    }
+
+    /// The settings in effect, as TOML
+    pub fn to_toml(&self) -> anyhow::Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+}
+
+/// Write the commented config template to `path`, keeping an existing file unless `force`
+pub fn init_config(path: &Path, force: bool) -> anyhow::Result<()> {
+    if path.exists() && !force {
+        bail!(
+            "{} already exists, use --force to overwrite it",
+            path.display()
+        );
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("cannot create {}", dir.display()))?;
+    }
+    std::fs::write(path, CONFIG_TEMPLATE)
+        .with_context(|| format!("cannot write {}", path.display()))
+}
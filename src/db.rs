@@ -1,83 +1,209 @@
-use std::path::PathBuf;
-
-use crate::model::{Commitment, CommitmentWithProgress, WeeklyStat};
-use chrono::{Datelike, Duration, Local, NaiveDate};
-use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
-
-fn db_path() -> PathBuf {
-    let mut path = PathBuf::from("./data");
-    std::fs::create_dir_all(&path).unwrap();
-    path.push("weekly_commit.db");
-    path
-}
+use std::{collections::HashMap, path::Path};
+
+use crate::model::{
+    CarryOver, CarryOverKind, Commitment, CommitmentWithProgress, DeletePreview, GoalDirection,
+    InactivePeriod, Milestone, NewCommitment, PlanEntry, ProgressLog, UnitKind, WeeklyStat,
+};
+use crate::util::{get_monday_this_week, monday_of, weekly_stats_from_days};
+use chrono::{Duration, Local, NaiveDate};
+use sqlx::{
+    SqlitePool,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+};
 
 fn current_week_bounds() -> (NaiveDate, NaiveDate) {
-    let today = Local::now().date_naive();
-    let weekday = today.weekday().num_days_from_monday() as i64;
-
-    let week_start = today - Duration::days(weekday);
+    let week_start = get_monday_this_week();
     let next_week_start = week_start + Duration::days(7);
 
     (week_start, next_week_start)
 }
 
-pub async fn open_db() -> SqlitePool {
-    let path = db_path().to_string_lossy().to_string();
-    let url = format!("sqlite://{}", path);
+pub async fn open_db(path: &Path) -> SqlitePool {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).unwrap();
+    }
+    let url = format!("sqlite://{}", path.to_string_lossy());
+    let options = url
+        .parse::<SqliteConnectOptions>()
+        .expect("Invalid DB path")
+        .foreign_keys(true);
 
     SqlitePoolOptions::new()
         .max_connections(5)
-        .connect(&url)
+        .connect_with(options)
         .await
         .expect("Failed to open DB")
 }
 
-pub async fn add_commitment(
-    pool: &SqlitePool,
-    name: &str,
-    weekly_hours: f64,
-) -> Result<i64, sqlx::Error> {
-    let today = Local::now().date_naive();
-    let today_str = today.to_string();
-    let weekday = today.weekday().num_days_from_monday() as i64;
+pub async fn add_commitment(pool: &SqlitePool, new: &NewCommitment) -> Result<i64, sqlx::Error> {
+    let today_str = Local::now().date_naive().to_string();
+    let week_start_str = new.start_monday.to_string();
 
-    // Monday of upcoming week
-    let week_start = today - Duration::days(weekday - 7);
-    let week_start_str = week_start.to_string();
+    let unit_kind = new.unit.kind();
+    let unit_label = new.unit.label();
+    let carry_over_kind = new.carry_over.kind();
+    let carry_over_value = new.carry_over.value();
+    let end_week_str = new.end_monday.map(|end| end.to_string());
 
-    let row = sqlx::query!(
+    // `execute` rather than `RETURNING` + `fetch_one`: a statement left mid-step keeps its
+    // implicit transaction open, and the insert may never get committed
+    let result = sqlx::query!(
         r#"
-        INSERT INTO commitments (name, weekly_target_hours, active, created_at, start_week_monday) 
-        VALUES (?1, ?2, 1, ?3, ?4)
-        RETURNING id;
+        INSERT INTO commitments (name, weekly_target_hours, active, created_at, start_week_monday, goal_direction, unit_kind, unit_label, weekly_target_sessions, carry_over, carry_over_value, parent_id, end_week_monday) 
+        VALUES (?1, ?2, 1, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12);
         "#,
-        name,
-        weekly_hours,
+        new.name,
+        new.weekly_target_hours,
         today_str,
-        week_start_str
+        week_start_str,
+        new.goal_direction,
+        unit_kind,
+        unit_label,
+        new.weekly_target_sessions,
+        carry_over_kind,
+        carry_over_value,
+        new.parent_id,
+        end_week_str
     )
-    .fetch_one(pool)
+    .execute(pool)
     .await?;
 
-    Ok(row.id)
+    Ok(result.last_insert_rowid())
 }
 
 pub async fn archive_commiment(pool: &SqlitePool, id: i64) -> Result<u64, sqlx::Error> {
+    archive_commitments(pool, &[id]).await
+}
+
+/// Archive several commitments at once, either all of them or none when one fails
+pub async fn archive_commitments(pool: &SqlitePool, ids: &[i64]) -> Result<u64, sqlx::Error> {
+    let today_str = Local::now().date_naive().to_string();
+    let mut tx = pool.begin().await?;
+    let mut archived = 0;
+
+    for id in ids {
+        let result = sqlx::query!(
+            r#"
+            UPDATE commitments
+            SET active = 0
+            WHERE id = ?1 AND active = 1;
+            "#,
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            sqlx::query!(
+                r#"
+                INSERT INTO inactive_periods (commitment_id, archived_on)
+                VALUES (?1, ?2);
+                "#,
+                id,
+                today_str
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        archived += result.rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(archived)
+}
+
+/// Count what `delete_commitment` would remove, `None` if there's no such commitment
+pub async fn delete_preview(
+    pool: &SqlitePool,
+    id: i64,
+) -> Result<Option<DeletePreview>, sqlx::Error> {
+    let preview = sqlx::query_as!(
+        DeletePreview,
+        r#"
+        SELECT
+            c.id as "id!: i64",
+            c.name,
+            (SELECT COUNT(*) FROM progress_logs pl WHERE pl.commitment_id = c.id) as "logs!: i64",
+            (SELECT COUNT(*) FROM commitments s WHERE s.parent_id = c.id) as "sub_commitments!: i64"
+        FROM commitments c
+        WHERE c.id = ?1;
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(preview)
+}
+
+/// Remove a commitment for good, along with its logs, plans and inactive periods.
+///
+/// Its sub-commitments are kept and become top-level.
+pub async fn delete_commitment(pool: &SqlitePool, id: i64) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM progress_logs WHERE commitment_id = ?1;
+        "#,
+        id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM week_plans WHERE commitment_id = ?1;
+        "#,
+        id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM inactive_periods WHERE commitment_id = ?1;
+        "#,
+        id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        UPDATE commitments SET parent_id = NULL WHERE parent_id = ?1;
+        "#,
+        id
+    )
+    .execute(&mut *tx)
+    .await?;
+
     let result = sqlx::query!(
         r#"
-        UPDATE commitments
-        SET active = 0
-        WHERE id = ?1 AND active = 1;
+        DELETE FROM commitments WHERE id = ?1;
         "#,
         id
     )
-    .execute(pool)
+    .execute(&mut *tx)
     .await?;
 
+    tx.commit().await?;
     Ok(result.rows_affected())
 }
 
-pub async fn reactivate_commiment(pool: &SqlitePool, id: i64) -> Result<u64, sqlx::Error> {
+/// Reactivate an archived commitment, closing its inactive period.
+///
+/// With `fresh`, the commitment starts over from the current week and its past debt or surplus
+/// is dropped.
+pub async fn reactivate_commiment(
+    pool: &SqlitePool,
+    id: i64,
+    fresh: bool,
+) -> Result<u64, sqlx::Error> {
+    let today = Local::now().date_naive();
+    let today_str = today.to_string();
+    let mut tx = pool.begin().await?;
+
     let result = sqlx::query!(
         r#"
         UPDATE commitments
@@ -86,76 +212,216 @@ pub async fn reactivate_commiment(pool: &SqlitePool, id: i64) -> Result<u64, sql
         "#,
         id
     )
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() > 0 {
+        sqlx::query!(
+            r#"
+            UPDATE inactive_periods
+            SET reactivated_on = ?2
+            WHERE commitment_id = ?1 AND reactivated_on IS NULL;
+            "#,
+            id,
+            today_str
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if fresh {
+            let (week_start, _) = current_week_bounds();
+            let week_start_str = week_start.to_string();
+            sqlx::query!(
+                r#"
+                UPDATE commitments
+                SET start_week_monday = ?2
+                WHERE id = ?1;
+                "#,
+                id,
+                week_start_str
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn set_carry_over(
+    pool: &SqlitePool,
+    id: i64,
+    carry_over: CarryOver,
+) -> Result<u64, sqlx::Error> {
+    let kind = carry_over.kind();
+    let value = carry_over.value();
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE commitments
+        SET carry_over = ?2, carry_over_value = ?3
+        WHERE id = ?1;
+        "#,
+        id,
+        kind,
+        value
+    )
     .execute(pool)
     .await?;
 
     Ok(result.rows_affected())
 }
 
-pub async fn get_commitment(pool: &SqlitePool, id: i64) -> Result<Option<Commitment>, sqlx::Error> {
-    let row = sqlx::query_as!(
-        Commitment,
+/// Move a commitment under `parent_id`, or make it top-level again with `None`
+pub async fn set_parent(
+    pool: &SqlitePool,
+    id: i64,
+    parent_id: Option<i64>,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
         r#"
-        SELECT 
-            id as "id!: i64", 
-            name as "name!: String", 
-            weekly_target_hours as "weekly_target_hours!: f64", 
-            active as "active!: bool"
-        FROM commitments
-        WHERE id == ?1
+        UPDATE commitments
+        SET parent_id = ?2
+        WHERE id = ?1;
+        "#,
+        id,
+        parent_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// The id of a commitment along with the ids of all its sub-commitments, at any depth
+pub async fn descendant_ids(pool: &SqlitePool, id: i64) -> Result<Vec<i64>, sqlx::Error> {
+    let rows = sqlx::query_scalar!(
+        r#"
+        SELECT id as "id!: i64"
+        FROM commitment_descendants
+        WHERE root_id = ?1
         "#,
         id
     )
-    .fetch_optional(pool)
+    .fetch_all(pool)
     .await?;
 
-    Ok(row)
+    Ok(rows)
 }
 
-pub async fn log_record(pool: &SqlitePool, name: &str, hours: f32) -> Result<i64, sqlx::Error> {
-    let log_time = Local::now().date_naive().to_string();
+/// Set the milestone of a commitment, or remove it with `None`
+pub async fn set_milestone(
+    pool: &SqlitePool,
+    id: i64,
+    milestone: Option<Milestone>,
+) -> Result<u64, sqlx::Error> {
+    let total = milestone.map(|m| m.total);
+    let due = milestone.map(|m| m.due.to_string());
 
-    let row = sqlx::query!(
+    let result = sqlx::query!(
         r#"
-        INSERT INTO progress_logs (commitment_id, hours, logged_at) 
-        SELECT id, ?2, ?3
+        UPDATE commitments
+        SET milestone_total = ?2, milestone_due = ?3
+        WHERE id = ?1;
+        "#,
+        id,
+        total,
+        due
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Set the last week of a commitment, or remove its end with `None`
+pub async fn set_end_week(
+    pool: &SqlitePool,
+    id: i64,
+    end_monday: Option<NaiveDate>,
+) -> Result<u64, sqlx::Error> {
+    let end_str = end_monday.map(|end| end.to_string());
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE commitments
+        SET end_week_monday = ?2
+        WHERE id = ?1;
+        "#,
+        id,
+        end_str
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Archive the active commitments whose end week is over, returning how many were archived
+pub async fn archive_ended_commitments(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+    let (this_monday, _) = current_week_bounds();
+    let this_monday_str = this_monday.to_string();
+    let today_str = Local::now().date_naive().to_string();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO inactive_periods (commitment_id, archived_on)
+        SELECT id, ?2
         FROM commitments
-        WHERE name = ?1 AND active = 1
-        RETURNING id;
+        WHERE active = 1
+          AND end_week_monday IS NOT NULL
+          AND end_week_monday < ?1;
         "#,
-        name,
-        hours,
-        log_time
+        this_monday_str,
+        today_str
     )
-    .fetch_one(pool)
+    .execute(&mut *tx)
     .await?;
 
-    Ok(row.id)
+    let result = sqlx::query!(
+        r#"
+        UPDATE commitments
+        SET active = 0
+        WHERE active = 1
+          AND end_week_monday IS NOT NULL
+          AND end_week_monday < ?1;
+        "#,
+        this_monday_str
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(result.rows_affected())
 }
 
 pub async fn log_record_id(
     pool: &SqlitePool,
     commitment_id: i64,
-    hours: f32,
+    amount: f64,
+    note: Option<&str>,
 ) -> Result<i64, sqlx::Error> {
     let log_time = Local::now().date_naive().to_string();
 
-    let row = sqlx::query!(
+    let result = sqlx::query!(
         r#"
-        INSERT INTO progress_logs (commitment_id, hours, logged_at) 
-        VALUES (?1, ?2, ?3)
-        RETURNING id;
+        INSERT INTO progress_logs (commitment_id, hours, logged_at, note) 
+        VALUES (?1, ?2, ?3, ?4);
         "#,
         commitment_id,
-        hours,
-        log_time
+        amount,
+        log_time,
+        note
     )
-    .fetch_one(pool)
+    .execute(pool)
     .await?;
 
-    Ok(row.id)
+    Ok(result.last_insert_rowid())
 }
 
+/// Total logged this week to a commitment and its sub-commitments, archived or not
 pub async fn current_week_progress_by_id(
     pool: &SqlitePool,
     commitment_id: i64,
@@ -168,9 +434,9 @@ pub async fn current_week_progress_by_id(
         r#"
         SELECT SUM(pl.hours) as "total: f64"
         FROM progress_logs pl
-        JOIN commitments c ON pl.commitment_id = c.id
+        JOIN commitment_descendants d ON d.id = pl.commitment_id
+        JOIN commitments c ON d.root_id = c.id
         WHERE c.id = ?1
-            AND c.active = 1
             AND pl.logged_at >= ?2
             AND pl.logged_at < ?3
         "#,
@@ -186,28 +452,48 @@ pub async fn current_week_progress_by_id(
 
 pub async fn list_active_commitments_with_week_progress(
     pool: &SqlitePool,
+) -> Result<Vec<CommitmentWithProgress>, sqlx::Error> {
+    let mut commitments = list_all_commitments_with_week_progress(pool).await?;
+    commitments.retain(|c| c.commitment.active);
+
+    Ok(commitments)
+}
+
+pub async fn list_all_commitments_with_week_progress(
+    pool: &SqlitePool,
 ) -> Result<Vec<CommitmentWithProgress>, sqlx::Error> {
     let (start, end) = current_week_bounds();
     let start_str = start.to_string();
     let end_str = end.to_string();
 
-    let rows = sqlx::query_as!(
-        CommitmentWithProgress,
+    let rows = sqlx::query!(
         r#"
         SELECT
             c.id as "id!: i64",
             c.name as "name!: String",
-            c.weekly_target_hours as "weekly_target_hours!: f64",
+            r.weekly_target_hours as "weekly_target_hours!: f64",
+            c.weekly_target_sessions,
             c.active as "active!: bool",
             c.start_week_monday as "start_monday!: NaiveDate",
-            SUM(pl.hours) as "current_week_total: f64"
+            c.end_week_monday as "end_monday: NaiveDate",
+            c.goal_direction as "goal_direction!: GoalDirection",
+            c.unit_kind as "unit_kind!: UnitKind",
+            c.unit_label,
+            c.carry_over as "carry_over_kind!: CarryOverKind",
+            c.carry_over_value,
+            c.parent_id,
+            c.milestone_total,
+            c.milestone_due as "milestone_due: NaiveDate",
+            SUM(pl.hours) as "current_week_total: f64",
+            COUNT(DISTINCT pl.logged_at) as "current_week_sessions!: i64"
         FROM commitments c
+        JOIN commitment_rollups r ON r.id = c.id
+        JOIN commitment_descendants d ON d.root_id = c.id
         LEFT JOIN progress_logs pl
-            ON pl.commitment_id = c.id
+            ON pl.commitment_id = d.id
            AND pl.logged_at >= ?1
            AND pl.logged_at < ?2
-        WHERE c.active = 1
-        GROUP BY c.id, c.name, c.weekly_target_hours
+        GROUP BY c.id
         "#,
         start_str,
         end_str
@@ -215,40 +501,64 @@ pub async fn list_active_commitments_with_week_progress(
     .fetch_all(pool)
     .await?;
 
-    Ok(rows)
+    let mut inactive = inactive_periods_by_commitment(pool).await?;
+    let commitments = rows
+        .into_iter()
+        .map(|r| CommitmentWithProgress {
+            commitment: Commitment {
+                id: r.id,
+                name: r.name,
+                weekly_target_hours: r.weekly_target_hours,
+                weekly_target_sessions: r.weekly_target_sessions,
+                start_monday: monday_of(r.start_monday),
+                end_monday: r.end_monday.map(monday_of),
+                inactive: inactive.remove(&r.id).unwrap_or_default(),
+                goal_direction: r.goal_direction,
+                unit_kind: r.unit_kind,
+                unit_label: r.unit_label,
+                carry_over_kind: r.carry_over_kind,
+                carry_over_value: r.carry_over_value,
+                parent_id: r.parent_id,
+                milestone_total: r.milestone_total,
+                milestone_due: r.milestone_due,
+                active: r.active,
+            },
+            current_week_total: r.current_week_total,
+            current_week_sessions: r.current_week_sessions,
+        })
+        .collect();
+
+    Ok(commitments)
 }
 
-pub async fn list_all_commitments_with_week_progress(
+/// The times every commitment was archived, oldest first, by commitment id
+async fn inactive_periods_by_commitment(
     pool: &SqlitePool,
-) -> Result<Vec<CommitmentWithProgress>, sqlx::Error> {
-    let (start, end) = current_week_bounds();
-    let start_str = start.to_string();
-    let end_str = end.to_string();
-
-    let rows = sqlx::query_as!(
-        CommitmentWithProgress,
+) -> Result<HashMap<i64, Vec<InactivePeriod>>, sqlx::Error> {
+    let rows = sqlx::query!(
         r#"
         SELECT
-            c.id as "id!: i64",
-            c.name as "name!: String",
-            c.weekly_target_hours as "weekly_target_hours!: f64",
-            c.active as "active!: bool",
-            c.start_week_monday as "start_monday!: NaiveDate",
-            SUM(pl.hours) as "current_week_total: f64"
-        FROM commitments c
-        LEFT JOIN progress_logs pl
-            ON pl.commitment_id = c.id
-           AND pl.logged_at >= ?1
-           AND pl.logged_at < ?2
-        GROUP BY c.id, c.name, c.weekly_target_hours
-        "#,
-        start_str,
-        end_str
+            commitment_id as "commitment_id!: i64",
+            archived_on as "archived_on!: NaiveDate",
+            reactivated_on as "reactivated_on: NaiveDate"
+        FROM inactive_periods
+        ORDER BY id
+        "#
     )
     .fetch_all(pool)
     .await?;
 
-    Ok(rows)
+    let mut periods: HashMap<i64, Vec<InactivePeriod>> = HashMap::new();
+    for r in rows {
+        periods
+            .entry(r.commitment_id)
+            .or_default()
+            .push(InactivePeriod {
+                archived_on: r.archived_on,
+                reactivated_on: r.reactivated_on,
+            });
+    }
+    Ok(periods)
 }
 
 pub async fn weekly_stats_for_commitment(
@@ -258,25 +568,173 @@ pub async fn weekly_stats_for_commitment(
     let rows = sqlx::query!(
         r#"
         SELECT
-            date(pl.logged_at, 'weekday 1', '-7 days') as "week_start!: NaiveDate",
+            pl.logged_at as "logged_at!: NaiveDate",
             SUM(pl.hours) as "total_hours!: f64"
         FROM progress_logs pl
-        WHERE pl.commitment_id = ?1
-        GROUP BY 1
-        ORDER BY 1
+        JOIN commitment_descendants d ON d.id = pl.commitment_id
+        WHERE d.root_id = ?1
+        GROUP BY pl.logged_at
         "#,
         commitment_id
     )
     .fetch_all(pool)
     .await?;
 
-    let stats = rows
+    let days: Vec<(NaiveDate, f64)> = rows
         .into_iter()
-        .map(|r| WeeklyStat {
-            week_start: r.week_start,
-            total_hours: r.total_hours,
+        .map(|r| (r.logged_at, r.total_hours))
+        .collect();
+
+    Ok(weekly_stats_from_days(&days))
+}
+
+pub async fn list_all_commitments(pool: &SqlitePool) -> Result<Vec<Commitment>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT 
+            c.id as "id!: i64", 
+            c.name as "name!: String", 
+            r.weekly_target_hours as "weekly_target_hours!: f64", 
+            c.weekly_target_sessions,
+            c.start_week_monday as "start_monday!: NaiveDate",
+            c.end_week_monday as "end_monday: NaiveDate",
+            c.goal_direction as "goal_direction!: GoalDirection",
+            c.unit_kind as "unit_kind!: UnitKind",
+            c.unit_label,
+            c.carry_over as "carry_over_kind!: CarryOverKind",
+            c.carry_over_value,
+            c.parent_id,
+            c.milestone_total,
+            c.milestone_due as "milestone_due: NaiveDate",
+            c.active as "active!: bool"
+        FROM commitments c
+        JOIN commitment_rollups r ON r.id = c.id
+        ORDER BY c.id
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut inactive = inactive_periods_by_commitment(pool).await?;
+    let commitments = rows
+        .into_iter()
+        .map(|r| Commitment {
+            id: r.id,
+            name: r.name,
+            weekly_target_hours: r.weekly_target_hours,
+            weekly_target_sessions: r.weekly_target_sessions,
+            start_monday: monday_of(r.start_monday),
+            end_monday: r.end_monday.map(monday_of),
+            inactive: inactive.remove(&r.id).unwrap_or_default(),
+            goal_direction: r.goal_direction,
+            unit_kind: r.unit_kind,
+            unit_label: r.unit_label,
+            carry_over_kind: r.carry_over_kind,
+            carry_over_value: r.carry_over_value,
+            parent_id: r.parent_id,
+            milestone_total: r.milestone_total,
+            milestone_due: r.milestone_due,
+            active: r.active,
         })
         .collect();
 
-    Ok(stats)
+    Ok(commitments)
+}
+
+/// All the logs recorded in the week starting on `week_start`, oldest first
+pub async fn logs_for_week(
+    pool: &SqlitePool,
+    week_start: NaiveDate,
+) -> Result<Vec<ProgressLog>, sqlx::Error> {
+    let start_str = week_start.to_string();
+    let end_str = (week_start + Duration::days(7)).to_string();
+
+    let rows = sqlx::query_as!(
+        ProgressLog,
+        r#"
+        SELECT
+            commitment_id as "commitment_id!: i64",
+            hours as "hours!: f64",
+            logged_at as "logged_at!: NaiveDate",
+            note
+        FROM progress_logs
+        WHERE logged_at >= ?1
+          AND logged_at < ?2
+        ORDER BY logged_at, id
+        "#,
+        start_str,
+        end_str
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// The plan entries of every commitment for the week starting on `week_start`
+pub async fn plans_for_week(
+    pool: &SqlitePool,
+    week_start: NaiveDate,
+) -> Result<Vec<PlanEntry>, sqlx::Error> {
+    let week_str = week_start.to_string();
+
+    let rows = sqlx::query_as!(
+        PlanEntry,
+        r#"
+        SELECT
+            commitment_id as "commitment_id!: i64",
+            weekday as "weekday!: i64",
+            planned_hours as "planned_hours!: f64"
+        FROM week_plans
+        WHERE week_start = ?1
+        ORDER BY commitment_id, weekday
+        "#,
+        week_str
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Replace the plan of a commitment for the week starting on `week_start`, Monday first.
+///
+/// Passing `None` removes the plan so the week goes back to an even split.
+pub async fn set_week_plan(
+    pool: &SqlitePool,
+    commitment_id: i64,
+    week_start: NaiveDate,
+    days: Option<&[f64; 7]>,
+) -> Result<(), sqlx::Error> {
+    let week_str = week_start.to_string();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM week_plans
+        WHERE commitment_id = ?1 AND week_start = ?2
+        "#,
+        commitment_id,
+        week_str
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    for (weekday, planned) in days.into_iter().flatten().enumerate() {
+        let weekday = weekday as i64;
+        sqlx::query!(
+            r#"
+            INSERT INTO week_plans (commitment_id, week_start, weekday, planned_hours)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+            commitment_id,
+            week_str,
+            weekday,
+            planned
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await
 }
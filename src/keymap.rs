@@ -0,0 +1,790 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use anyhow::{Context, bail};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+/// Everything that can be bound to a key in normal mode of the TUI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    Quit,
+    Help,
+    Down,
+    Up,
+    First,
+    Last,
+    Fold,
+    Create,
+    Log,
+    Presets,
+    Reactivate,
+    ReactivateFresh,
+    Archive,
+    Delete,
+    ToggleView,
+    Older,
+    Newer,
+    PageOlder,
+    PageNewer,
+    ZoomIn,
+    ZoomOut,
+    Search,
+    NextMatch,
+    PreviousMatch,
+    FilterActive,
+    FilterArchived,
+    FilterBehind,
+    ClearFilter,
+}
+
+impl Action {
+    /// Every action, in the order the help overlay lists them
+    pub const ALL: [Action; 28] = [
+        Action::Quit,
+        Action::Help,
+        Action::Down,
+        Action::Up,
+        Action::First,
+        Action::Last,
+        Action::Fold,
+        Action::Create,
+        Action::Log,
+        Action::Presets,
+        Action::Reactivate,
+        Action::ReactivateFresh,
+        Action::Archive,
+        Action::Delete,
+        Action::ToggleView,
+        Action::Older,
+        Action::Newer,
+        Action::PageOlder,
+        Action::PageNewer,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::Search,
+        Action::NextMatch,
+        Action::PreviousMatch,
+        Action::FilterActive,
+        Action::FilterArchived,
+        Action::FilterBehind,
+        Action::ClearFilter,
+    ];
+
+    /// The actions shown in the footer, the rest are only in the help overlay
+    const FOOTER: [Action; 13] = [
+        Action::Help,
+        Action::Search,
+        Action::Quit,
+        Action::Down,
+        Action::Up,
+        Action::Fold,
+        Action::Create,
+        Action::Log,
+        Action::Presets,
+        Action::Reactivate,
+        Action::Archive,
+        Action::Delete,
+        Action::ToggleView,
+    ];
+
+    /// The name in the `[keys]` table of the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Help => "help",
+            Action::Down => "down",
+            Action::Up => "up",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::Fold => "fold",
+            Action::Create => "create",
+            Action::Log => "log",
+            Action::Presets => "presets",
+            Action::Reactivate => "reactivate",
+            Action::ReactivateFresh => "reactivate-fresh",
+            Action::Archive => "archive",
+            Action::Delete => "delete",
+            Action::ToggleView => "toggle-view",
+            Action::Older => "older",
+            Action::Newer => "newer",
+            Action::PageOlder => "page-older",
+            Action::PageNewer => "page-newer",
+            Action::ZoomIn => "zoom-in",
+            Action::ZoomOut => "zoom-out",
+            Action::Search => "search",
+            Action::NextMatch => "next-match",
+            Action::PreviousMatch => "previous-match",
+            Action::FilterActive => "filter-active",
+            Action::FilterArchived => "filter-archived",
+            Action::FilterBehind => "filter-behind",
+            Action::ClearFilter => "clear-filter",
+        }
+    }
+
+    /// Short name for the footer
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Help => "help",
+            Action::Down => "down",
+            Action::Up => "up",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::Fold => "fold",
+            Action::Create => "add commitment",
+            Action::Log => "log",
+            Action::Presets => "presets",
+            Action::Reactivate => "reactivate",
+            Action::ReactivateFresh => "reactivate fresh",
+            Action::Archive => "archive",
+            Action::Delete => "delete",
+            Action::ToggleView => "chart view",
+            Action::Older => "week back",
+            Action::Newer => "week forward",
+            Action::PageOlder => "page back",
+            Action::PageNewer => "page forward",
+            Action::ZoomIn => "zoom in",
+            Action::ZoomOut => "zoom out",
+            Action::Search => "search",
+            Action::NextMatch => "next match",
+            Action::PreviousMatch => "previous match",
+            Action::FilterActive => "active only",
+            Action::FilterArchived => "archived only",
+            Action::FilterBehind => "behind only",
+            Action::ClearFilter => "clear filter",
+        }
+    }
+
+    /// What the action does, for the help overlay
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Help => "Show this help",
+            Action::Down => "Select the next commitment, or [count] down",
+            Action::Up => "Select the previous commitment, or [count] up",
+            Action::First => "Jump to the first commitment, or to row [count]",
+            Action::Last => "Jump to the last commitment, or to row [count]",
+            Action::Fold => "Fold or unfold the sub-commitments",
+            Action::Create => "Add a commitment",
+            Action::Log => "Log progress to the selected commitment",
+            Action::Presets => "Log one of the presets",
+            Action::Reactivate => "Reactivate an archived commitment",
+            Action::ReactivateFresh => "Reactivate, starting over from this week",
+            Action::Archive => "Archive the selected commitment, or [count] from it on",
+            Action::Delete => "Delete the selected commitment",
+            Action::ToggleView => "Switch between the weekly and cumulative chart",
+            Action::Older => "Scroll the weekly chart [count] weeks back",
+            Action::Newer => "Scroll the weekly chart [count] weeks forward",
+            Action::PageOlder => "Scroll the weekly chart [count] pages back",
+            Action::PageNewer => "Scroll the weekly chart [count] pages forward",
+            Action::ZoomIn => "Widen the bars of the weekly chart",
+            Action::ZoomOut => "Narrow the bars of the weekly chart",
+            Action::Search => "Search the list by name, narrowing it down while typing",
+            Action::NextMatch => "Select the next match of the search or filter",
+            Action::PreviousMatch => "Select the previous match of the search or filter",
+            Action::FilterActive => "Show only active commitments, or all again",
+            Action::FilterArchived => "Show only archived commitments, or all again",
+            Action::FilterBehind => "Show only commitments behind this week's plan, or all again",
+            Action::ClearFilter => "Clear the search and the filter",
+        }
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q"],
+            Action::Help => &["?"],
+            Action::Down => &["j", "down"],
+            Action::Up => &["k", "up"],
+            Action::First => &["gg", "ctrl-g"],
+            Action::Last => &["G"],
+            Action::Fold => &["space"],
+            Action::Create => &["c"],
+            Action::Log => &["l"],
+            Action::Presets => &["p"],
+            Action::Reactivate => &["r"],
+            Action::ReactivateFresh => &["R"],
+            Action::Archive => &["aa"],
+            Action::Delete => &["dd", "D"],
+            Action::ToggleView => &["v"],
+            Action::Older => &["left"],
+            Action::Newer => &["right"],
+            Action::PageOlder => &["["],
+            Action::PageNewer => &["]"],
+            Action::ZoomIn => &["+", "="],
+            Action::ZoomOut => &["-"],
+            Action::Search => &["/"],
+            Action::NextMatch => &["n"],
+            Action::PreviousMatch => &["N"],
+            Action::FilterActive => &["fa"],
+            Action::FilterArchived => &["fr"],
+            Action::FilterBehind => &["fb"],
+            Action::ClearFilter => &["esc"],
+        }
+    }
+}
+
+/// A single key press together with its modifiers, such as `ctrl-g`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    /// Shift is part of the character itself (`G`), so it's only kept for the other keys
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            KeyCode::BackTab => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            code => code,
+        };
+        Self { code, modifiers }
+    }
+
+    /// A plain character without modifiers, which sequences write without spaces (`gg`)
+    fn is_plain_char(&self) -> bool {
+        matches!(self.code, KeyCode::Char(c) if c != ' ') && self.modifiers.is_empty()
+    }
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(key: KeyEvent) -> Self {
+        KeyChord::new(key.code, key.modifiers)
+    }
+}
+
+/// Names of the keys that aren't a single character
+const NAMED_KEYS: [(&str, KeyCode); 17] = [
+    ("space", KeyCode::Char(' ')),
+    ("enter", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("tab", KeyCode::Tab),
+    ("backtab", KeyCode::BackTab),
+    ("backspace", KeyCode::Backspace),
+    ("delete", KeyCode::Delete),
+    ("insert", KeyCode::Insert),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("minus", KeyCode::Char('-')),
+];
+
+fn parse_key_name(name: &str) -> Option<KeyCode> {
+    let lower = name.to_lowercase();
+    if let Some((_, code)) = NAMED_KEYS.iter().find(|(key, _)| *key == lower) {
+        return Some(*code);
+    }
+    match name {
+        "←" => return Some(KeyCode::Left),
+        "→" => return Some(KeyCode::Right),
+        "↑" => return Some(KeyCode::Up),
+        "↓" => return Some(KeyCode::Down),
+        _ => {}
+    }
+    if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok())
+        && (1..=12).contains(&n)
+    {
+        return Some(KeyCode::F(n));
+    }
+
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(KeyCode::Char(c)),
+        _ => None,
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The key comes after the last `-`, except for the minus key itself as in `-` or `ctrl--`
+        let (prefixes, key) = match s.strip_suffix("--") {
+            Some(prefixes) => (prefixes, "-"),
+            None if s == "-" => ("", "-"),
+            None => s.rsplit_once('-').unwrap_or(("", s)),
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for prefix in prefixes.split('-').filter(|prefix| !prefix.is_empty()) {
+            modifiers |= match prefix.to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier '{prefix}' in '{s}'")),
+            };
+        }
+
+        match parse_key_name(key) {
+            Some(code) => Ok(KeyChord::new(code, modifiers)),
+            None => Err(format!("unknown key '{key}' in '{s}'")),
+        }
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::F(n) => write!(f, "f{n}"),
+            code => match NAMED_KEYS.iter().find(|(_, named)| *named == code) {
+                Some((name, _)) => write!(f, "{name}"),
+                None => write!(f, "{code:?}"),
+            },
+        }
+    }
+}
+
+/// One or more key presses in a row, written as `gg`, `ctrl-x d` or `space`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySequence(Vec<KeyChord>);
+
+impl FromStr for KeySequence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chords = Vec::new();
+        for token in s.split_whitespace() {
+            match token.parse::<KeyChord>() {
+                Ok(chord) => chords.push(chord),
+                // A run of plain characters such as `gg` is one key press per character
+                Err(_) if !token.contains('-') && token.chars().count() > 1 => {
+                    chords.extend(
+                        token
+                            .chars()
+                            .map(|c| KeyChord::new(KeyCode::Char(c), KeyModifiers::NONE)),
+                    );
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        if chords.is_empty() {
+            return Err("a key binding cannot be empty".to_string());
+        }
+        Ok(KeySequence(chords))
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, chord) in self.0.iter().enumerate() {
+            let joined = idx > 0 && chord.is_plain_char() && self.0[idx - 1].is_plain_char();
+            if idx > 0 && !joined {
+                write!(f, " ")?;
+            }
+            write!(f, "{chord}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The keys of one action in the config file, a single binding or a list of them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyBinding {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyBinding {
+    fn keys(&self) -> Vec<&str> {
+        match self {
+            KeyBinding::One(key) => vec![key.as_str()],
+            KeyBinding::Many(keys) => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// What the keys pressed so far amount to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lookup {
+    Action(Action),
+    /// The start of a longer sequence, waiting for the next key
+    Pending,
+    Unbound,
+}
+
+/// The key sequences bound to every action in normal mode
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(KeySequence, Action)>,
+}
+
+impl Keymap {
+    /// The default bindings, with the actions in `overrides` bound to their keys instead
+    pub fn new(overrides: &BTreeMap<Action, KeyBinding>) -> anyhow::Result<Self> {
+        let mut bindings: Vec<(KeySequence, Action)> = Vec::new();
+        for action in Action::ALL {
+            let keys = match overrides.get(&action) {
+                Some(binding) => binding.keys(),
+                None => action.default_keys().to_vec(),
+            };
+            let name = action.name();
+            for key in keys {
+                let sequence: KeySequence = key
+                    .parse()
+                    .map_err(anyhow::Error::msg)
+                    .with_context(|| format!("in keys.{name}"))?;
+
+                if let Some((other_sequence, other)) = bindings.iter().find(|(other, _)| {
+                    other.0.starts_with(&sequence.0) || sequence.0.starts_with(&other.0)
+                }) {
+                    if other_sequence == &sequence {
+                        bail!("'{sequence}' is bound to both {} and {name}", other.name());
+                    }
+                    bail!(
+                        "'{sequence}' of {name} and '{other_sequence}' of {} start the same way, so one of them could never be pressed",
+                        other.name()
+                    );
+                }
+                bindings.push((sequence, action));
+            }
+        }
+        Ok(Self { bindings })
+    }
+
+    /// Match the keys pressed so far against the bindings
+    pub fn lookup(&self, pressed: &[KeyChord]) -> Lookup {
+        let mut lookup = Lookup::Unbound;
+        for (sequence, action) in &self.bindings {
+            if sequence.0 == pressed {
+                return Lookup::Action(*action);
+            }
+            if sequence.0.starts_with(pressed) {
+                lookup = Lookup::Pending;
+            }
+        }
+        lookup
+    }
+
+    /// Every key sequence bound to `action`, e.g. `j/↓`
+    pub fn keys_for(&self, action: Action) -> String {
+        self.bindings
+            .iter()
+            .filter(|(_, bound)| *bound == action)
+            .map(|(sequence, _)| sequence.to_string())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// The first key sequence bound to `action`, `-` when there is none
+    pub fn key_for(&self, action: Action) -> String {
+        self.bindings
+            .iter()
+            .find(|(_, bound)| *bound == action)
+            .map_or_else(|| "-".to_string(), |(sequence, _)| sequence.to_string())
+    }
+
+    /// The key hints of normal mode for the footer, each with the action it stands for
+    pub fn footer_hints(&self) -> Vec<(String, Action)> {
+        Action::FOOTER
+            .iter()
+            .filter(|action| self.bindings.iter().any(|(_, bound)| bound == *action))
+            .map(|action| {
+                (
+                    format!("{}: {}", self.key_for(*action), action.label()),
+                    *action,
+                )
+            })
+            .collect()
+    }
+
+    /// The key hints of normal mode for the footer, as one line
+    pub fn footer_hint(&self) -> String {
+        self.footer_hints()
+            .into_iter()
+            .map(|(hint, _)| hint)
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+}
+
+/// Counts above this are cut off, nothing in the TUI needs more
+const MAX_COUNT: usize = 9999;
+
+/// The keys typed so far in normal mode: a count such as the `5` of `5j`, followed by the start
+/// of a key sequence such as the first `g` of `gg`
+#[derive(Debug, Clone, Default)]
+pub struct KeyBuffer {
+    count: Option<usize>,
+    keys: Vec<KeyChord>,
+}
+
+impl KeyBuffer {
+    /// Add a key press, returning the action and count once the keys make up a binding.
+    ///
+    /// Keys that don't continue any binding are dropped together with the count, the last one is
+    /// then tried on its own. `esc` drops whatever was typed so far.
+    pub fn press(&mut self, keymap: &Keymap, key: KeyEvent) -> Option<(Action, Option<usize>)> {
+        let chord = KeyChord::from(key);
+        if chord.code == KeyCode::Esc && !self.is_empty() {
+            self.clear();
+            return None;
+        }
+
+        // Digits start a count unless they are bound themselves, `0` only continues one
+        if self.keys.is_empty()
+            && chord.modifiers.is_empty()
+            && let KeyCode::Char(c) = chord.code
+            && let Some(digit) = c.to_digit(10)
+            && (digit > 0 || self.count.is_some())
+            && keymap.lookup(&[chord]) == Lookup::Unbound
+        {
+            let count = self.count.unwrap_or(0) * 10 + digit as usize;
+            self.count = Some(count.min(MAX_COUNT));
+            return None;
+        }
+
+        self.keys.push(chord);
+        match keymap.lookup(&self.keys) {
+            Lookup::Action(action) => {
+                let count = self.count;
+                self.clear();
+                Some((action, count))
+            }
+            Lookup::Pending => None,
+            Lookup::Unbound => {
+                let retry = self.keys.len() > 1;
+                self.clear();
+                if retry { self.press(keymap, key) } else { None }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.count = None;
+        self.keys.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count.is_none() && self.keys.is_empty()
+    }
+}
+
+/// The typed count and keys as they are shown in the footer, e.g. `5g`
+impl fmt::Display for KeyBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(count) = self.count {
+            write!(f, "{count}")?;
+        }
+        if !self.keys.is_empty() {
+            write!(f, "{}", KeySequence(self.keys.clone()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(code: KeyCode, modifiers: KeyModifiers) -> KeyChord {
+        KeyChord::new(code, modifiers)
+    }
+
+    fn char_key(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    /// Feed `keys` to a fresh buffer, returning every action it produced
+    fn press_all(keymap: &Keymap, keys: &[KeyEvent]) -> Vec<(Action, Option<usize>)> {
+        let mut buffer = KeyBuffer::default();
+        keys.iter()
+            .filter_map(|key| buffer.press(keymap, *key))
+            .collect()
+    }
+
+    fn default_keymap() -> Keymap {
+        Keymap::new(&BTreeMap::new()).expect("Default keys conflict")
+    }
+
+    #[test]
+    fn parses_chords_with_modifiers() {
+        assert_eq!(
+            "ctrl-g".parse(),
+            Ok(chord(KeyCode::Char('g'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            "ctrl-alt-x".parse(),
+            Ok(chord(
+                KeyCode::Char('x'),
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            ))
+        );
+        assert_eq!(
+            "Enter".parse(),
+            Ok(chord(KeyCode::Enter, KeyModifiers::NONE))
+        );
+        assert_eq!("f5".parse(), Ok(chord(KeyCode::F(5), KeyModifiers::NONE)));
+        assert_eq!("←".parse(), Ok(chord(KeyCode::Left, KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn shift_is_part_of_characters() {
+        let upper_g = chord(KeyCode::Char('G'), KeyModifiers::NONE);
+        assert_eq!("G".parse(), Ok(upper_g));
+        assert_eq!("shift-g".parse(), Ok(upper_g));
+        assert_eq!(
+            KeyChord::from(KeyEvent::new(KeyCode::Char('g'), KeyModifiers::SHIFT)),
+            upper_g
+        );
+        assert_eq!(
+            "shift-left".parse(),
+            Ok(chord(KeyCode::Left, KeyModifiers::SHIFT))
+        );
+    }
+
+    #[test]
+    fn parses_the_minus_key() {
+        let minus = chord(KeyCode::Char('-'), KeyModifiers::NONE);
+        assert_eq!("-".parse(), Ok(minus));
+        assert_eq!("minus".parse(), Ok(minus));
+        assert_eq!(
+            "ctrl--".parse(),
+            Ok(chord(KeyCode::Char('-'), KeyModifiers::CONTROL))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_chords() {
+        assert!("hyper-x".parse::<KeyChord>().is_err());
+        assert!("ctrl-nope".parse::<KeyChord>().is_err());
+        assert!("gg".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn sequences_with_and_without_spaces_are_the_same() {
+        let gg: KeySequence = "gg".parse().unwrap();
+        assert_eq!(gg, "g g".parse().unwrap());
+        assert_eq!(gg.0.len(), 2);
+        assert_eq!(gg.to_string(), "gg");
+
+        let ctrl_x_d: KeySequence = "ctrl-x d".parse().unwrap();
+        assert_eq!(ctrl_x_d.0.len(), 2);
+        assert_eq!(ctrl_x_d.to_string(), "ctrl-x d");
+        assert_eq!("space".parse::<KeySequence>().unwrap().to_string(), "space");
+    }
+
+    #[test]
+    fn rejects_invalid_sequences() {
+        assert!("".parse::<KeySequence>().is_err());
+        assert!("   ".parse::<KeySequence>().is_err());
+        assert!("ctrl-xd".parse::<KeySequence>().is_err());
+    }
+
+    #[test]
+    fn rejects_conflicting_bindings() {
+        let same = BTreeMap::from([(Action::Quit, KeyBinding::One("j".to_string()))]);
+        assert!(Keymap::new(&same).is_err());
+
+        // `g` alone would make `gg` of first impossible to press
+        let prefix = BTreeMap::from([(Action::Last, KeyBinding::One("g".to_string()))]);
+        assert!(Keymap::new(&prefix).is_err());
+
+        let moved = BTreeMap::from([
+            (Action::Quit, KeyBinding::Many(vec!["ctrl-c".to_string()])),
+            (Action::Last, KeyBinding::One("q".to_string())),
+        ]);
+        let keymap = Keymap::new(&moved).unwrap();
+        assert_eq!(keymap.keys_for(Action::Last), "q");
+        assert_eq!(keymap.key_for(Action::Quit), "ctrl-c");
+    }
+
+    #[test]
+    fn presses_single_keys_and_sequences() {
+        let keymap = default_keymap();
+        assert_eq!(press_all(&keymap, &[char_key('j')]), [(Action::Down, None)]);
+        assert_eq!(
+            press_all(&keymap, &[char_key('g'), char_key('g')]),
+            [(Action::First, None)]
+        );
+        assert_eq!(
+            press_all(
+                &keymap,
+                &[KeyEvent::new(KeyCode::Char('g'), KeyModifiers::CONTROL)]
+            ),
+            [(Action::First, None)]
+        );
+    }
+
+    #[test]
+    fn counts_come_before_keys_and_sequences() {
+        let keymap = default_keymap();
+        assert_eq!(
+            press_all(&keymap, &[char_key('5'), char_key('j')]),
+            [(Action::Down, Some(5))]
+        );
+        assert_eq!(
+            press_all(&keymap, &[char_key('1'), char_key('0'), char_key('k')]),
+            [(Action::Up, Some(10))]
+        );
+        assert_eq!(
+            press_all(&keymap, &[char_key('3'), char_key('a'), char_key('a')]),
+            [(Action::Archive, Some(3))]
+        );
+        // A leading zero is not a count
+        assert_eq!(
+            press_all(&keymap, &[char_key('0'), char_key('j')]),
+            [(Action::Down, None)]
+        );
+    }
+
+    #[test]
+    fn broken_sequences_retry_the_last_key() {
+        let keymap = default_keymap();
+        assert_eq!(
+            press_all(&keymap, &[char_key('g'), char_key('j')]),
+            [(Action::Down, None)]
+        );
+        assert_eq!(
+            press_all(&keymap, &[char_key('4'), char_key('g'), char_key('x')]),
+            []
+        );
+    }
+
+    #[test]
+    fn esc_drops_what_was_typed() {
+        let keymap = default_keymap();
+        let mut buffer = KeyBuffer::default();
+        assert_eq!(buffer.press(&keymap, char_key('5')), None);
+        assert_eq!(buffer.press(&keymap, char_key('g')), None);
+        assert_eq!(buffer.to_string(), "5g");
+
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        assert_eq!(buffer.press(&keymap, esc), None);
+        assert!(buffer.is_empty());
+        // With nothing typed, esc is a key of its own
+        assert_eq!(
+            buffer.press(&keymap, esc),
+            Some((Action::ClearFilter, None))
+        );
+    }
+}
@@ -1,65 +1,357 @@
 mod app;
 mod cli;
+mod completions;
+mod config;
 mod db;
+mod keymap;
 mod model;
+mod prompt;
+mod report;
+mod selector;
+mod stats;
+mod theme;
 mod tui;
 mod ui;
+mod unit;
 mod util;
 
+use chrono::{Datelike, Duration, Local};
 use clap::Parser;
 use cli::Cli;
+use sqlx::SqlitePool;
 
 use crate::{
-    cli::Commands,
+    cli::{Commands, ConfigAction},
+    completions::{print_commitment_candidates, print_completions},
+    config::{Config, config_path, init_config},
     db::{
-        add_commitment, archive_commiment, current_week_progress_by_id, get_commitment,
-        list_active_commitments_with_week_progress, log_record, log_record_id, open_db,
-        reactivate_commiment, weekly_stats_for_commitment,
+        add_commitment, archive_commiment, archive_ended_commitments, current_week_progress_by_id,
+        delete_commitment, delete_preview, descendant_ids,
+        list_active_commitments_with_week_progress, list_all_commitments, log_record_id,
+        logs_for_week, open_db, plans_for_week, reactivate_commiment, set_carry_over, set_end_week,
+        set_milestone, set_parent, set_week_plan, weekly_stats_for_commitment,
+    },
+    model::{Commitment, GoalDirection, Milestone, NewCommitment, WeekPlan},
+    prompt::{
+        confirm, is_interactive, missing_argument, pick_commitment, prompt_amount, prompt_text,
+        prompt_week,
+    },
+    report::{build_weekly_report, render_report},
+    selector::{Selector, select_commitment},
+    stats::{compute_commitment_stats, forecast_week, milestone_progress},
+    util::{
+        Tone, carried_balance, cumulative_series, get_monday_this_week, monday_of, paint,
+        render_progress_bar, session_totals, set_week_start, tone_for_pct, tree_order,
+        weekly_hours_since,
     },
-    util::{color_for_pct, render_progress_bar},
 };
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
 
-    let pool = open_db().await;
+    // Neither completion scripts nor writing the config file need the config or the database
+    let config_file = cli.config.clone().or_else(config_path);
+    match &cli.command {
+        Some(Commands::Completions { shell }) => {
+            print_completions(*shell);
+            return Ok(());
+        }
+        // Runs on every TAB press, so it only reads and copes with a config that doesn't load
+        Some(Commands::CompleteCommitments { shell }) => {
+            let mut config = Config::load(cli.config.as_deref()).unwrap_or_default();
+            if let Some(db) = &cli.db {
+                config.database.path = db.clone();
+            }
+            if let Ok(start) = cli
+                .week_start
+                .as_deref()
+                .unwrap_or(&config.week.start)
+                .parse()
+            {
+                set_week_start(start);
+            }
+            let pool = open_db(&config.database.path).await;
+            print_commitment_candidates(&pool, *shell).await?;
+            return Ok(());
+        }
+        Some(Commands::Config {
+            action: ConfigAction::Path,
+        }) => {
+            match &config_file {
+                Some(path) => println!("{}", path.display()),
+                None => eprintln!(
+                    "Cannot locate the config file, neither XDG_CONFIG_HOME nor HOME is set."
+                ),
+            }
+            return Ok(());
+        }
+        Some(Commands::Config {
+            action: ConfigAction::Init { force },
+        }) => {
+            let Some(path) = &config_file else {
+                eprintln!(
+                    "Cannot locate the config file, neither XDG_CONFIG_HOME nor HOME is set."
+                );
+                return Ok(());
+            };
+            match init_config(path, *force) {
+                Ok(()) => println!("Wrote {}.", path.display()),
+                Err(e) => eprintln!("{e:#}."),
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let mut config = match Config::load(cli.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{e:#}");
+            std::process::exit(1);
+        }
+    };
+    if let Some(db) = cli.db {
+        config.database.path = db;
+    }
+    if let Some(start) = cli.week_start {
+        config.week.start = start;
+    }
+    if let Some(theme) = cli.theme {
+        config.tui.theme = theme;
+    }
+    if let Some(tick_ms) = cli.tick_ms {
+        config.tui.tick_ms = tick_ms;
+    }
+    if let Err(e) = config.validate() {
+        eprintln!("{e:#}");
+        std::process::exit(1);
+    }
+    set_week_start(config.week_start());
+
+    if let Some(Commands::Config {
+        action: ConfigAction::Show,
+    }) = &cli.command
+    {
+        if let Some(path) = config_file.filter(|path| path.exists()) {
+            println!("# {}\n", path.display());
+        }
+        print!("{}", config.to_toml()?);
+        return Ok(());
+    }
+
+    let pool = open_db(&config.database.path).await;
+
+    let num_ended = archive_ended_commitments(&pool).await?;
+    if num_ended > 0 && cli.command.is_some() {
+        eprintln!("Archived {num_ended} commitment(s) whose end week is over.");
+    }
 
     match cli.command {
         // If there is no command then should start the tui app
-        None => tui::run_tui(pool).await?,
+        None => tui::run_tui(pool, &config).await?,
 
         // Otherwise act as a cli tool
         Some(command) => {
             match command {
-                Commands::Add { name, weekly_hours } => {
-                    let id = add_commitment(&pool, &name, weekly_hours).await?;
+                Commands::Add {
+                    name,
+                    target,
+                    limit,
+                    unit,
+                    sessions,
+                    carry_over,
+                    parent,
+                    start,
+                    end,
+                } => {
+                    let prompting = name.is_none() || target.is_none();
+                    if prompting && !is_interactive() {
+                        missing_argument("add", if name.is_none() { "name" } else { "target" });
+                    }
+
+                    let name = match name {
+                        Some(name) => name,
+                        None => prompt_text("Name")?,
+                    };
+                    let weekly_target = match target {
+                        Some(target) => match unit.parse_amount(&target) {
+                            Ok(value) => value,
+                            Err(e) => {
+                                eprintln!("Invalid target '{target}': {e}.");
+                                return Ok(());
+                            }
+                        },
+                        None => prompt_amount(
+                            if limit {
+                                "Weekly limit"
+                            } else {
+                                "Weekly target"
+                            },
+                            &unit,
+                        )?,
+                    };
+                    let direction = if limit {
+                        GoalDirection::AtMost
+                    } else {
+                        GoalDirection::AtLeast
+                    };
+                    let mut parent_id = None;
+                    if let Some(parent) = parent {
+                        let Some(p) = select(&pool, &parent).await? else {
+                            return Ok(());
+                        };
+                        parent_id = Some(p.id);
+                        // Targets are rolled up into the parent, so they have to add up
+                        if p.unit() != unit || p.goal_direction != direction {
+                            eprintln!(
+                                "Sub-commitments must use the same unit and kind of target as '{}' ({}{}).",
+                                p.name,
+                                if p.goal_direction.is_limit() {
+                                    "limit in "
+                                } else {
+                                    ""
+                                },
+                                p.unit()
+                            );
+                            return Ok(());
+                        }
+                    }
+                    // Commitments start on the upcoming monday unless told otherwise
+                    let next_monday = get_monday_this_week() + Duration::days(7);
+                    let start_monday = match start {
+                        Some(start) => monday_of(start),
+                        None if prompting => prompt_week("Start week", next_monday)?,
+                        None => next_monday,
+                    };
+                    let end_monday = end.map(monday_of);
+                    if let Some(end_monday) = end_monday {
+                        if end_monday <= get_monday_this_week() {
+                            eprintln!("The end week has to be after the current one.");
+                            return Ok(());
+                        }
+                        if end_monday < start_monday {
+                            eprintln!("The end week cannot be before the start week.");
+                            return Ok(());
+                        }
+                    }
+
+                    let new = NewCommitment {
+                        name,
+                        weekly_target_hours: weekly_target,
+                        weekly_target_sessions: sessions,
+                        goal_direction: direction,
+                        unit,
+                        carry_over,
+                        parent_id,
+                        start_monday,
+                        end_monday,
+                    };
+                    let id = add_commitment(&pool, &new).await?;
 
                     println!(
-                        "Added commitment #{id}: '{}' ({} hours/week)",
-                        name, weekly_hours
+                        "Added commitment #{id}: '{}' ({}{}{}/week)",
+                        new.name,
+                        if limit { "at most " } else { "" },
+                        new.unit.format(weekly_target),
+                        sessions
+                            .map(|n| format!(" over {n} sessions"))
+                            .unwrap_or_default()
                     );
                 }
 
-                Commands::Archive { id } => {
+                Commands::Archive { commitment } => {
+                    let Some(c) = select(&pool, &commitment).await? else {
+                        return Ok(());
+                    };
+                    let id = c.id;
                     let num_archived = archive_commiment(&pool, id).await?;
                     if num_archived > 0 {
                         println!(
                             "Marked commitment #{id} as inactive. (Affected {num_archived} rows)"
                         );
                     } else {
-                        eprintln!("No active commitment with id {id}.")
+                        eprintln!("'{}' is already archived.", c.name)
                     }
                 }
 
-                Commands::Reactivate { id } => {
-                    let num_reactivated = reactivate_commiment(&pool, id).await?;
+                Commands::Delete {
+                    commitment,
+                    dry_run,
+                    yes,
+                } => {
+                    let Some(c) = select(&pool, &commitment).await? else {
+                        return Ok(());
+                    };
+                    let id = c.id;
+                    let Some(preview) = delete_preview(&pool, id).await? else {
+                        return Ok(());
+                    };
+                    if !dry_run && !yes {
+                        if !is_interactive() {
+                            eprintln!(
+                                "Not deleting #{id} without confirmation, pass --yes to delete it anyway."
+                            );
+                            return Ok(());
+                        }
+                        let question = format!(
+                            "Delete commitment #{id} '{}' and its {} log(s) for good?",
+                            preview.name, preview.logs
+                        );
+                        if !confirm(&question)? {
+                            println!("Nothing was deleted.");
+                            return Ok(());
+                        }
+                    }
+                    if dry_run {
+                        println!(
+                            "Would delete commitment #{id} '{}' and {} log(s).",
+                            preview.name, preview.logs
+                        );
+                    } else {
+                        delete_commitment(&pool, id).await?;
+                        println!(
+                            "Deleted commitment #{id} '{}' and {} log(s).",
+                            preview.name, preview.logs
+                        );
+                    }
+                    if preview.sub_commitments > 0 {
+                        println!(
+                            "{} sub-commitment(s) {} top-level.",
+                            preview.sub_commitments,
+                            if dry_run { "would become" } else { "became" }
+                        );
+                    }
+                }
+
+                Commands::Reactivate { commitment, fresh } => {
+                    let Some(c) = select(&pool, &commitment).await? else {
+                        return Ok(());
+                    };
+                    let id = c.id;
+                    if let Some(end) = c.end_monday
+                        && end < get_monday_this_week()
+                    {
+                        eprintln!(
+                            "'{}' ended in the week of {end}, move its end with `set-end` first.",
+                            c.name
+                        );
+                        return Ok(());
+                    }
+
+                    let num_reactivated = reactivate_commiment(&pool, id, fresh).await?;
                     if num_reactivated > 0 {
                         println!(
                             "Marked commitment #{id} as active. (Affected {num_reactivated} rows)"
                         );
+                        if fresh {
+                            println!(
+                                "Starting fresh from the week of {}.",
+                                get_monday_this_week()
+                            );
+                        }
                     } else {
-                        eprintln!("No inactive commiment with id {id}.");
+                        eprintln!("'{}' is already active.", c.name);
                     }
                 }
 
@@ -68,10 +360,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     if commitments.is_empty() {
                         println!("No active commiments.");
                     } else {
-                        commitments
-                            .sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+                        commitments.sort_by_key(|c| c.commitment.name.to_lowercase());
                         println!("Active commiments:\n");
-                        for commitment in commitments {
+                        let today = Local::now().date_naive();
+                        for (commitment, depth) in
+                            tree_order(commitments, |c| c.commitment.id, |c| c.commitment.parent_id)
+                        {
+                            let unit = commitment.commitment.unit();
                             let current = commitment.current_week_total.unwrap_or(0.0);
                             let status_note = if commitment.current_week_total.is_none() {
                                 " (Haven't started this week...)"
@@ -79,122 +374,608 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                                 ""
                             };
 
-                            let pct = if commitment.weekly_target_hours > 0.0 {
-                                (current / commitment.weekly_target_hours * 100.0).clamp(0.0, 999.9)
+                            let pct = if commitment.commitment.weekly_target_hours > 0.0 {
+                                (current / commitment.commitment.weekly_target_hours * 100.0)
+                                    .clamp(0.0, 999.9)
                             } else {
                                 0.0
                             };
 
+                            let amounts = format!(
+                                "{}/{}",
+                                unit.format_value(current),
+                                unit.format(commitment.commitment.weekly_target_hours)
+                            );
+
                             let message = format!(
-                                "{cur:.1}/{target:.1} h ({pct:.1}%){note}",
-                                cur = current,
-                                target = commitment.weekly_target_hours,
+                                "{amounts} ({pct:.1}%){note}",
                                 pct = pct,
                                 note = status_note
                             );
 
                             let bar = render_progress_bar(
                                 current,
-                                commitment.weekly_target_hours,
+                                commitment.commitment.weekly_target_hours,
                                 message.len() + 5, // +2 for the "  " before the message
                             );
 
-                            let color = color_for_pct(pct);
-                            const RESET: &str = "\x1b[0m";
+                            let tone = tone_for_pct(
+                                pct,
+                                commitment.commitment.goal_direction,
+                                &config.progress,
+                            );
 
                             let colored_message = format!(
-                                "{cur:.1}/{target:.1} h {color}({pct:.1}%){RESET}\x1b[31m{note}\x1b[0m",
-                                cur = current,
-                                target = commitment.weekly_target_hours,
-                                pct = pct,
-                                note = status_note
+                                "{amounts} {pct}{note}",
+                                pct = paint(format!("({pct:.1}%)"), tone),
+                                note = paint(status_note, Tone::Bad)
                             );
 
                             println!(
-                                "[#{id}] {name}\n {bar}  {message}",
-                                id = commitment.id,
-                                name = commitment.name,
+                                "{indent}[#{id}] {name}\n {bar}  {message}",
+                                indent = "    ".repeat(depth),
+                                id = commitment.commitment.id,
+                                name = commitment.commitment.name,
                                 bar = bar,
                                 message = colored_message
                             );
+
+                            if let Some(target) = commitment.commitment.weekly_target_sessions {
+                                let done = commitment.current_week_sessions;
+                                let tone = tone_for_pct(
+                                    done as f64 / target as f64 * 100.0,
+                                    GoalDirection::AtLeast,
+                                    &config.progress,
+                                );
+                                println!(" Sessions: {}", paint(format!("{done}/{target}"), tone));
+                            }
+
+                            let forecast = forecast_week(
+                                current,
+                                commitment.commitment.weekly_target_hours,
+                                today,
+                            );
+                            let pace = if commitment.commitment.goal_direction.is_limit() {
+                                format!(
+                                    "{}/day still allowed ({} days left)",
+                                    unit.format(forecast.required_daily),
+                                    forecast.days_left
+                                )
+                            } else if forecast.required_daily > 0.0 {
+                                format!(
+                                    "needs {}/day ({} days left)",
+                                    unit.format(forecast.required_daily),
+                                    forecast.days_left
+                                )
+                            } else {
+                                "target reached".to_string()
+                            };
+                            println!(
+                                " Pace: {pace}, projected {} by Sunday",
+                                unit.format(forecast.projected_total)
+                            );
                         }
                     }
                 }
 
-                Commands::LogID {
-                    id: commitment_id,
-                    hours,
+                Commands::Log {
+                    commitment,
+                    amount,
+                    note,
                 } => {
-                    let id = log_record_id(&pool, commitment_id, hours).await?;
+                    // A preset stands in for the commitment, and for the amount and note unless
+                    // they are given too
+                    let (commitment, amount, note) =
+                        match commitment.as_ref().and_then(Selector::preset_name) {
+                            Some(name) => {
+                                let Some(preset) = config.preset(name) else {
+                                    eprintln!("There is no preset named '@{name}'.");
+                                    return Ok(());
+                                };
+                                (
+                                    Some(preset.commitment.parse::<Selector>()?),
+                                    amount.or_else(|| Some(preset.amount.clone())),
+                                    note.or_else(|| preset.note.clone()),
+                                )
+                            }
+                            None => (commitment, amount, note),
+                        };
 
-                    println!(
-                        "Logged record #{id} for commitment #{commitment_id} for {hours} hours."
-                    );
-                }
+                    let c = match commitment {
+                        Some(commitment) => {
+                            let Some(c) = select(&pool, &commitment).await? else {
+                                return Ok(());
+                            };
+                            c
+                        }
+                        None => {
+                            if !is_interactive() {
+                                missing_argument("log", "commitment");
+                            }
+                            let mut active = list_all_commitments(&pool).await?;
+                            active.retain(|c| c.active);
+                            active.sort_by_key(|c| c.name.to_lowercase());
+                            if active.is_empty() {
+                                eprintln!("No active commitments to log progress for.");
+                                return Ok(());
+                            }
+                            let Some(c) = pick_commitment("Log progress for", &active)? else {
+                                return Ok(());
+                            };
+                            c
+                        }
+                    };
+                    if !c.active {
+                        eprintln!(
+                            "'{}' is archived, reactivate it before logging progress.",
+                            c.name
+                        );
+                        return Ok(());
+                    }
+                    let unit = c.unit();
+                    let value = match amount {
+                        Some(amount) => match unit.parse_amount(&amount) {
+                            Ok(value) => value,
+                            Err(e) => {
+                                eprintln!("Invalid amount '{amount}': {e}.");
+                                return Ok(());
+                            }
+                        },
+                        None => {
+                            if !is_interactive() {
+                                missing_argument("log", "amount");
+                            }
+                            prompt_amount("Amount", &unit)?
+                        }
+                    };
 
-                Commands::Log { name, hours } => {
-                    let id = log_record(&pool, name.as_str(), hours).await?;
+                    let id = log_record_id(&pool, c.id, value, note.as_deref()).await?;
 
-                    println!("Logged record #{id} for commitment '{name}' for {hours} hours.");
+                    println!(
+                        "Logged record #{id} for commitment '{}' (#{}) for {}.",
+                        c.name,
+                        c.id,
+                        unit.format(value)
+                    );
                 }
 
-                Commands::TrackID { id } => {
-                    let week_total = current_week_progress_by_id(&pool, id).await?;
-                    let commitment = get_commitment(&pool, id).await?;
-
-                    if let Some(wk) = week_total
-                        && let Some(ct) = commitment
-                    {
-                        assert!(ct.id == id);
+                Commands::Track { commitment } => {
+                    let Some(ct) = select(&pool, &commitment).await? else {
+                        return Ok(());
+                    };
 
+                    if let Some(wk) = current_week_progress_by_id(&pool, ct.id).await? {
                         if !ct.active {
                             eprintln!("The activity is currently not active.");
                         }
 
+                        let unit = ct.unit();
                         println!(
                             "Current week progress for task '{}' is {}/{}",
-                            ct.name, wk, ct.weekly_target_hours
+                            ct.name,
+                            unit.format_value(wk),
+                            unit.format(ct.weekly_target_hours)
                         );
-                    } else if commitment.is_none() {
-                        eprintln!("Cannot find commitment #{id}.");
-                    } else if week_total.is_none() {
-                        let ct = commitment.unwrap();
+                    } else {
                         eprintln!("You have not started on task '{}' this week.", ct.name);
                     }
                 }
 
-                Commands::History { id } => {
-                    let commitment = get_commitment(&pool, id).await?;
+                Commands::History {
+                    commitment,
+                    cumulative,
+                } => {
+                    let Some(c) = select(&pool, &commitment).await? else {
+                        return Ok(());
+                    };
+                    let id = c.id;
+                    println!("{:?}", c);
+                    let weekly_stats = weekly_stats_for_commitment(&pool, id).await?;
+
+                    let goal = c.goal();
+                    let weeks = weekly_hours_since(&goal, &weekly_stats);
+                    let total_hours_done = weeks.iter().fold(0.0, |acc, (_, hours)| acc + hours);
+                    let total_hours_target = weeks.len() as f64 * c.weekly_target_hours;
+                    let balance = carried_balance(&goal, &weeks);
+
+                    let unit = c.unit();
+                    println!(
+                        "done: {}\t\ttarget: {}\t\tcarry-over: {}",
+                        unit.format(total_hours_done),
+                        unit.format(total_hours_target),
+                        goal.carry_over
+                    );
 
-                    match commitment {
-                        Some(c) => {
-                            println!("{:?}", c);
-                            let weekly_stats = weekly_stats_for_commitment(&pool, id).await?;
+                    println!(
+                        "'{name}' is {status} by {amount}",
+                        name = c.name,
+                        status = match (c.goal_direction, balance < 0.0) {
+                            (GoalDirection::AtLeast, true) => "due",
+                            (GoalDirection::AtLeast, false) => "overcomplete",
+                            (GoalDirection::AtMost, true) => "under the limit",
+                            (GoalDirection::AtMost, false) => "over the limit",
+                        },
+                        amount = unit.format(balance.abs())
+                    );
 
-                            let total_hours_done =
-                                weekly_stats.iter().fold(0.0, |acc, w| acc + w.total_hours);
-                            let total_hours_target =
-                                weekly_stats.len() as f64 * c.weekly_target_hours;
+                    if let Some((done, required)) = session_totals(&goal, &weekly_stats) {
+                        println!("sessions done: {done}\ttarget: {required}");
+                        if done < required {
+                            println!("'{}' is behind by {} sessions", c.name, required - done);
+                        }
+                    }
 
-                            println!("done: {total_hours_done}\t\ttarget: {total_hours_target}");
+                    if cumulative {
+                        let series = cumulative_series(&goal, &weekly_stats);
 
+                        println!(
+                            "\n{:<12}{:>10}{:>12}{:>12}{:>10}",
+                            "week",
+                            unit.to_string(),
+                            "cum. done",
+                            "cum. req.",
+                            "balance"
+                        );
+                        for point in series {
                             println!(
-                                "'{name}' is {status} by {amount}",
-                                name = c.name,
-                                status = if total_hours_done < total_hours_target {
-                                    "due"
-                                } else {
-                                    "overcomplete"
-                                },
-                                amount = (total_hours_done - total_hours_target).abs()
+                                "{:<12}{:>10.1}{:>12.1}{:>12.1}{:>+10.1}",
+                                point.week_start.format("%Y-%m-%d").to_string(),
+                                point.week_hours,
+                                point.done,
+                                point.required,
+                                point.balance
                             );
                         }
-                        None => {
-                            eprintln!("Cannot find commitment #{id}");
+                    }
+                }
+
+                Commands::Stats { commitment, weeks } => {
+                    let Some(c) = select(&pool, &commitment).await? else {
+                        return Ok(());
+                    };
+                    let id = c.id;
+
+                    let weekly_stats = weekly_stats_for_commitment(&pool, id).await?;
+                    let stats = compute_commitment_stats(&c.goal(), &weekly_stats, weeks);
+
+                    let unit = c.unit();
+                    println!(
+                        "Statistics for '{}' (#{id}, {} {}/week)\n",
+                        c.name,
+                        if c.goal_direction.is_limit() {
+                            "limit"
+                        } else {
+                            "target"
+                        },
+                        unit.format(c.weekly_target_hours)
+                    );
+                    println!("Current streak:   {} weeks", stats.current_streak);
+                    println!("Longest streak:   {} weeks", stats.longest_streak);
+                    println!(
+                        "Hit rate:         {:.0}% ({}/{} of last {} weeks)",
+                        stats.hit_rate(),
+                        stats.recent_hits,
+                        stats.recent_weeks,
+                        weeks
+                    );
+                    println!("Average week:     {}", unit.format(stats.average_hours));
+                    println!("Median week:      {}", unit.format(stats.median_hours));
+                    if let Some((week, amount)) = stats.best_week {
+                        println!("Best week:        {} (week of {week})", unit.format(amount));
+                    }
+                    if let Some((week, amount)) = stats.worst_week {
+                        println!("Worst week:       {} (week of {week})", unit.format(amount));
+                    }
+                }
+
+                Commands::SetEnd { commitment, end } => {
+                    let Some(c) = select(&pool, &commitment).await? else {
+                        return Ok(());
+                    };
+                    let id = c.id;
+
+                    let end_monday = end.map(monday_of);
+                    if let Some(end_monday) = end_monday
+                        && end_monday < c.start_monday
+                    {
+                        eprintln!(
+                            "'{}' starts in the week of {}, it cannot end before that.",
+                            c.name, c.start_monday
+                        );
+                        return Ok(());
+                    }
+
+                    set_end_week(&pool, id, end_monday).await?;
+                    match end_monday {
+                        Some(end) => println!("'{}' now ends after the week of {end}.", c.name),
+                        None => println!("'{}' no longer has an end.", c.name),
+                    }
+                }
+
+                Commands::SetParent { commitment, parent } => {
+                    let Some(c) = select(&pool, &commitment).await? else {
+                        return Ok(());
+                    };
+                    let id = c.id;
+
+                    if let Some(parent) = parent {
+                        let Some(p) = select(&pool, &parent).await? else {
+                            return Ok(());
+                        };
+                        let parent_id = p.id;
+                        if descendant_ids(&pool, id).await?.contains(&parent_id) {
+                            eprintln!("'{}' cannot be moved under itself or its children.", c.name);
+                            return Ok(());
+                        }
+                        if p.unit() != c.unit() || p.goal_direction != c.goal_direction {
+                            eprintln!(
+                                "Sub-commitments must use the same unit and kind of target as '{}'.",
+                                p.name
+                            );
+                            return Ok(());
                         }
+
+                        set_parent(&pool, id, Some(parent_id)).await?;
+                        println!("Moved '{}' under '{}'.", c.name, p.name);
+                    } else {
+                        set_parent(&pool, id, None).await?;
+                        println!("Moved '{}' to the top level.", c.name);
                     }
                 }
 
+                Commands::CarryOver { commitment, policy } => {
+                    let Some(c) = select(&pool, &commitment).await? else {
+                        return Ok(());
+                    };
+                    set_carry_over(&pool, c.id, policy).await?;
+                    println!("Set the carry-over policy of '{}' to {policy}.", c.name);
+                }
+
+                Commands::Milestone {
+                    commitment,
+                    total,
+                    due,
+                    clear,
+                } => {
+                    let Some(c) = select(&pool, &commitment).await? else {
+                        return Ok(());
+                    };
+                    let id = c.id;
+
+                    if clear {
+                        set_milestone(&pool, id, None).await?;
+                        println!("Removed the milestone of '{}'.", c.name);
+                    } else if let (Some(total), Some(due)) = (total, due) {
+                        let unit = c.unit();
+                        let total = match unit.parse_amount(&total) {
+                            Ok(value) if value > 0.0 => value,
+                            Ok(_) => {
+                                eprintln!("The milestone total has to be more than zero.");
+                                return Ok(());
+                            }
+                            Err(e) => {
+                                eprintln!("Invalid total '{total}': {e}.");
+                                return Ok(());
+                            }
+                        };
+
+                        set_milestone(&pool, id, Some(Milestone { total, due })).await?;
+                        println!(
+                            "Set the milestone of '{}' to {} by {due}.",
+                            c.name,
+                            unit.format(total)
+                        );
+                    }
+                }
+
+                Commands::Milestones => {
+                    let today = Local::now().date_naive();
+                    let mut commitments = list_active_commitments_with_week_progress(&pool).await?;
+                    commitments.retain(|c| c.commitment.milestone().is_some());
+                    commitments.sort_by_key(|c| c.commitment.milestone_due);
+
+                    if commitments.is_empty() {
+                        println!("No active milestones.");
+                    }
+                    for c in commitments {
+                        let unit = c.commitment.unit();
+                        let stats = weekly_stats_for_commitment(&pool, c.commitment.id).await?;
+                        let Some(progress) =
+                            milestone_progress(&c.commitment.goal(), &stats, today)
+                        else {
+                            continue;
+                        };
+
+                        let countdown = match progress.days_left {
+                            d if d < 0 => paint(format!("{} days overdue", -d), Tone::Bad),
+                            0 => "due today".to_string(),
+                            d => format!("{d} days left"),
+                        };
+                        println!(
+                            "[#{}] {}: {}/{} by {} ({countdown})",
+                            c.commitment.id,
+                            c.commitment.name,
+                            unit.format_value(progress.done),
+                            unit.format(progress.milestone.total),
+                            progress.milestone.due
+                        );
+
+                        if progress.is_complete() {
+                            println!(" {}", paint("Reached", Tone::Good));
+                            continue;
+                        }
+                        let projection = match progress.projected_completion {
+                            Some(date) if progress.on_track() => {
+                                paint(format!("projected {date}"), Tone::Good)
+                            }
+                            Some(date) => paint(format!("projected {date}"), Tone::Bad),
+                            None => paint("no progress yet", Tone::Bad),
+                        };
+                        println!(
+                            " Needs {}/week, {projection}",
+                            unit.format(progress.required_weekly)
+                        );
+                    }
+                }
+
+                Commands::Plan {
+                    commitment,
+                    days,
+                    week,
+                    reset,
+                } => {
+                    let Some(c) = select(&pool, &commitment).await? else {
+                        return Ok(());
+                    };
+                    let id = c.id;
+                    let unit = c.unit();
+                    let week_start = week.map(monday_of).unwrap_or_else(get_monday_this_week);
+
+                    if reset {
+                        set_week_plan(&pool, id, week_start, None).await?;
+                        println!(
+                            "Reset the plan of '{}' for the week of {week_start}.",
+                            c.name
+                        );
+                    } else if !days.is_empty() {
+                        if days.len() != 7 {
+                            eprintln!(
+                                "Expected 7 amounts, one per day of the week, got {}.",
+                                days.len()
+                            );
+                            return Ok(());
+                        }
+
+                        // Amounts are given from the first day of the week, plans are kept Monday first
+                        let mut planned = [0.0; 7];
+                        for (offset, amount) in days.iter().enumerate() {
+                            let date = week_start + Duration::days(offset as i64);
+                            let value = match unit.parse_amount(amount) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    eprintln!("Invalid amount '{amount}': {e}.");
+                                    return Ok(());
+                                }
+                            };
+                            planned[date.weekday().num_days_from_monday() as usize] = value;
+                        }
+                        set_week_plan(&pool, id, week_start, Some(&planned)).await?;
+                        println!("Planned '{}' for the week of {week_start}.", c.name);
+                    }
+
+                    let entries = plans_for_week(&pool, week_start).await?;
+                    let plan = WeekPlan::from_entries(id, c.weekly_target_hours, &entries);
+                    println!(
+                        "\nPlan for '{}' (#{id}), week of {week_start} ({}):",
+                        c.name,
+                        if plan.custom { "custom" } else { "even split" }
+                    );
+                    for offset in 0..7 {
+                        let date = week_start + Duration::days(offset);
+                        println!(
+                            " {}  {}",
+                            date.format("%a %m-%d"),
+                            unit.format(plan.planned_on(date.weekday()))
+                        );
+                    }
+                    println!(
+                        " Total      {} (target {})",
+                        unit.format(plan.total()),
+                        unit.format(c.weekly_target_hours)
+                    );
+                }
+
+                Commands::Presets => {
+                    if config.presets.is_empty() {
+                        println!(
+                            "No presets, add them as [presets.<name>] to {}.",
+                            config_path()
+                                .map(|path| path.display().to_string())
+                                .unwrap_or_else(|| "the config file".to_string())
+                        );
+                    }
+                    for (name, preset) in &config.presets {
+                        println!(
+                            "@{name}: {} to '{}'{}",
+                            preset.amount,
+                            preset.commitment,
+                            preset
+                                .note
+                                .as_ref()
+                                .map(|note| format!(" ({note})"))
+                                .unwrap_or_default()
+                        );
+                    }
+                }
+
+                // Handled before opening the database
+                Commands::Completions { .. }
+                | Commands::CompleteCommitments { .. }
+                | Commands::Config { .. } => {}
+
+                Commands::Today => {
+                    let today = Local::now().date_naive();
+                    let week_start = monday_of(today);
+                    let mut commitments = list_active_commitments_with_week_progress(&pool).await?;
+                    commitments.sort_by_key(|c| c.commitment.name.to_lowercase());
+                    let entries = plans_for_week(&pool, week_start).await?;
+                    let logs = logs_for_week(&pool, week_start).await?;
+
+                    println!("Today ({}):\n", today.format("%a %Y-%m-%d"));
+                    for c in commitments {
+                        let unit = c.commitment.unit();
+                        let plan = WeekPlan::from_entries(
+                            c.commitment.id,
+                            c.commitment.weekly_target_hours,
+                            &entries,
+                        );
+                        let ids = descendant_ids(&pool, c.commitment.id).await?;
+                        let done_today = logs
+                            .iter()
+                            .filter(|l| ids.contains(&l.commitment_id) && l.logged_at == today)
+                            .fold(0.0, |acc, l| acc + l.hours);
+                        let done_week = c.current_week_total.unwrap_or(0.0);
+                        let expected = plan.expected_by(today.weekday(), config.week_start());
+
+                        let behind = match c.commitment.goal_direction {
+                            GoalDirection::AtLeast => expected - done_week,
+                            GoalDirection::AtMost => done_week - expected,
+                        };
+                        let status = if behind > 1e-6 {
+                            paint(
+                                format!(
+                                    "{} {}",
+                                    if c.commitment.goal_direction.is_limit() {
+                                        "over plan by"
+                                    } else {
+                                        "behind by"
+                                    },
+                                    unit.format(behind)
+                                ),
+                                Tone::Bad,
+                            )
+                        } else {
+                            paint("on track", Tone::Good)
+                        };
+
+                        println!("[#{}] {}", c.commitment.id, c.commitment.name);
+                        println!(
+                            " Today: {}/{} planned | Week: {}/{} expected by now, {status}",
+                            unit.format_value(done_today),
+                            unit.format(plan.planned_on(today.weekday())),
+                            unit.format_value(done_week),
+                            unit.format(expected)
+                        );
+                    }
+                }
+
+                Commands::Report { week, format } => {
+                    let week_start = week.map(monday_of).unwrap_or_else(get_monday_this_week);
+                    let entries = build_weekly_report(&pool, week_start).await?;
+
+                    print!("{}", render_report(format, week_start, &entries));
+                }
+
                 x => {
                     println!("{:?} not implemented yet.", x);
                 }
@@ -204,3 +985,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Resolve a commitment selector, printing why when it doesn't pick exactly one commitment
+async fn select(pool: &SqlitePool, selector: &Selector) -> Result<Option<Commitment>, sqlx::Error> {
+    match select_commitment(pool, selector).await? {
+        Ok(c) => Ok(Some(c)),
+        Err(e) => {
+            eprintln!("{e}");
+            Ok(None)
+        }
+    }
+}
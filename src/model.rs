@@ -1,25 +1,434 @@
-use chrono::NaiveDate;
+use std::{fmt, str::FromStr};
 
-#[derive(Debug, Clone, sqlx::FromRow)]
+use chrono::{NaiveDate, Weekday};
+
+use crate::{unit::Unit, util::monday_of};
+
+/// Whether the weekly target is a minimum to reach or a limit to stay under
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum GoalDirection {
+    AtLeast,
+    AtMost,
+}
+
+impl GoalDirection {
+    /// Whether `hours` done in a week satisfies a weekly target of `target`
+    pub fn meets(self, hours: f64, target: f64) -> bool {
+        match self {
+            GoalDirection::AtLeast => hours >= target,
+            GoalDirection::AtMost => hours <= target,
+        }
+    }
+
+    pub fn is_limit(self) -> bool {
+        self == GoalDirection::AtMost
+    }
+}
+
+/// How the unit of a commitment is stored, custom units keep their name in `unit_label`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum UnitKind {
+    Hours,
+    Minutes,
+    Count,
+    Distance,
+    Custom,
+}
+
+/// How the carry-over policy is stored, the cap or percentage is kept in `carry_over_value`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum CarryOverKind {
+    None,
+    Full,
+    Capped,
+    Decay,
+}
+
+/// What happens to the surplus or debt of past weeks when a new week starts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CarryOver {
+    /// Every week stands alone
+    None,
+    /// Everything is carried over forever
+    Full,
+    /// At most this amount is carried over, in either direction
+    Capped(f64),
+    /// This percentage of the balance is dropped every week
+    Decay(f64),
+}
+
+impl CarryOver {
+    pub fn from_parts(kind: CarryOverKind, value: Option<f64>) -> Self {
+        match kind {
+            CarryOverKind::None => CarryOver::None,
+            CarryOverKind::Full => CarryOver::Full,
+            CarryOverKind::Capped => CarryOver::Capped(value.unwrap_or(0.0)),
+            CarryOverKind::Decay => CarryOver::Decay(value.unwrap_or(0.0)),
+        }
+    }
+
+    pub fn kind(&self) -> CarryOverKind {
+        match self {
+            CarryOver::None => CarryOverKind::None,
+            CarryOver::Full => CarryOverKind::Full,
+            CarryOver::Capped(_) => CarryOverKind::Capped,
+            CarryOver::Decay(_) => CarryOverKind::Decay,
+        }
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        match self {
+            CarryOver::Capped(v) | CarryOver::Decay(v) => Some(*v),
+            CarryOver::None | CarryOver::Full => None,
+        }
+    }
+
+    /// The part of `balance` that is still there at the start of the next week
+    pub fn carry(&self, balance: f64) -> f64 {
+        match self {
+            CarryOver::None => 0.0,
+            CarryOver::Full => balance,
+            CarryOver::Capped(cap) => balance.clamp(-cap, *cap),
+            CarryOver::Decay(pct) => balance * (1.0 - pct / 100.0),
+        }
+    }
+}
+
+impl fmt::Display for CarryOver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CarryOver::None => write!(f, "none"),
+            CarryOver::Full => write!(f, "full"),
+            CarryOver::Capped(cap) => write!(f, "cap:{cap}"),
+            CarryOver::Decay(pct) => write!(f, "decay:{pct}%"),
+        }
+    }
+}
+
+impl FromStr for CarryOver {
+    type Err = String;
+
+    /// Accepts `none`, `full`, `cap:<amount>` and `decay:<percent>`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        let (name, value) = match s.split_once(':') {
+            Some((name, value)) => (name, Some(value.trim().trim_end_matches('%'))),
+            None => (s.as_str(), None),
+        };
+        let value = value
+            .map(|v| {
+                v.parse::<f64>()
+                    .ok()
+                    .filter(|v| v.is_finite() && *v >= 0.0)
+                    .ok_or_else(|| format!("'{v}' is not a valid amount"))
+            })
+            .transpose()?;
+
+        match (name, value) {
+            ("none", None) => Ok(CarryOver::None),
+            ("full", None) => Ok(CarryOver::Full),
+            ("cap" | "capped", Some(cap)) => Ok(CarryOver::Capped(cap)),
+            ("decay", Some(pct)) if pct <= 100.0 => Ok(CarryOver::Decay(pct)),
+            ("decay", Some(_)) => Err("decay must be a percentage up to 100".to_string()),
+            _ => Err(format!(
+                "unknown policy '{s}', expected none, full, cap:<amount> or decay:<percent>"
+            )),
+        }
+    }
+}
+
+/// A total to reach by a due date
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Milestone {
+    pub total: f64,
+    pub due: NaiveDate,
+}
+
+/// A time a commitment was archived, the weeks fully inside it are not counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InactivePeriod {
+    pub archived_on: NaiveDate,
+    pub reactivated_on: Option<NaiveDate>,
+}
+
+impl InactivePeriod {
+    /// Whether the week of `monday` is skipped: the weeks of archiving and of reactivating still
+    /// count, only the ones in between do not
+    pub fn excludes(&self, monday: NaiveDate) -> bool {
+        let archived_monday = monday_of(self.archived_on);
+        monday > archived_monday
+            && self
+                .reactivated_on
+                .is_none_or(|date| monday < monday_of(date))
+    }
+}
+
+/// The parts of a commitment that decide what counts as keeping it
+#[derive(Debug, Clone)]
+pub struct Goal {
+    pub start_monday: NaiveDate,
+    /// Last week of the commitment, if it has an end
+    pub end_monday: Option<NaiveDate>,
+    pub weekly_target_hours: f64,
+    /// Minimum number of sessions (distinct days with a log) per week, if any
+    pub weekly_target_sessions: Option<i64>,
+    pub direction: GoalDirection,
+    pub unit: Unit,
+    pub carry_over: CarryOver,
+    pub milestone: Option<Milestone>,
+    pub inactive: Vec<InactivePeriod>,
+}
+
+impl Goal {
+    /// The last week that counts while in the week of `this_monday`, which is the end week once
+    /// it is over
+    pub fn last_monday(&self, this_monday: NaiveDate) -> NaiveDate {
+        self.end_monday
+            .map_or(this_monday, |end| end.min(this_monday))
+    }
+
+    /// Whether the week of `monday` counts, i.e. the commitment was not archived for all of it
+    pub fn counts_week(&self, monday: NaiveDate) -> bool {
+        !self.inactive.iter().any(|period| period.excludes(monday))
+    }
+
+    /// Whether a week with `hours` done over `sessions` sessions keeps the commitment
+    pub fn is_met(&self, hours: f64, sessions: i64) -> bool {
+        self.direction.meets(hours, self.weekly_target_hours)
+            && self.weekly_target_sessions.is_none_or(|n| sessions >= n)
+    }
+}
+
+/// Everything needed to create a commitment
+#[derive(Debug, Clone)]
+pub struct NewCommitment {
+    pub name: String,
+    pub weekly_target_hours: f64,
+    pub weekly_target_sessions: Option<i64>,
+    pub goal_direction: GoalDirection,
+    pub unit: Unit,
+    pub carry_over: CarryOver,
+    pub parent_id: Option<i64>,
+    pub start_monday: NaiveDate,
+    pub end_monday: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone)]
 pub struct Commitment {
     pub id: i64,
     pub name: String,
     pub weekly_target_hours: f64,
+    pub weekly_target_sessions: Option<i64>,
+    pub start_monday: NaiveDate,
+    pub end_monday: Option<NaiveDate>,
+    /// When the commitment was archived, oldest first
+    pub inactive: Vec<InactivePeriod>,
+    pub goal_direction: GoalDirection,
+    pub unit_kind: UnitKind,
+    pub unit_label: Option<String>,
+    pub carry_over_kind: CarryOverKind,
+    pub carry_over_value: Option<f64>,
+    /// The project this commitment is part of, its logs also count toward the parent
+    pub parent_id: Option<i64>,
+    pub milestone_total: Option<f64>,
+    pub milestone_due: Option<NaiveDate>,
     pub active: bool,
 }
 
-#[derive(Debug, sqlx::FromRow)]
+impl Commitment {
+    pub fn unit(&self) -> Unit {
+        Unit::from_parts(self.unit_kind, self.unit_label.as_deref())
+    }
+
+    pub fn milestone(&self) -> Option<Milestone> {
+        Some(Milestone {
+            total: self.milestone_total?,
+            due: self.milestone_due?,
+        })
+    }
+
+    pub fn goal(&self) -> Goal {
+        Goal {
+            start_monday: self.start_monday,
+            end_monday: self.end_monday,
+            weekly_target_hours: self.weekly_target_hours,
+            weekly_target_sessions: self.weekly_target_sessions,
+            direction: self.goal_direction,
+            unit: self.unit(),
+            carry_over: CarryOver::from_parts(self.carry_over_kind, self.carry_over_value),
+            milestone: self.milestone(),
+            inactive: self.inactive.clone(),
+        }
+    }
+}
+
+/// A commitment with what was done this week, summed over its sub-commitments
+#[derive(Debug)]
 pub struct CommitmentWithProgress {
+    pub commitment: Commitment,
+    pub current_week_total: Option<f64>,
+    /// Distinct days with a log this week
+    pub current_week_sessions: i64,
+}
+
+/// What deleting a commitment would remove
+#[derive(Debug, Clone)]
+pub struct DeletePreview {
     pub id: i64,
     pub name: String,
-    pub weekly_target_hours: f64,
-    pub current_week_total: Option<f64>,
-    pub start_monday: NaiveDate,
-    pub active: bool,
+    pub logs: i64,
+    /// Direct sub-commitments, which are kept and become top-level
+    pub sub_commitments: i64,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ProgressLog {
+    pub commitment_id: i64,
+    pub hours: f64,
+    pub logged_at: NaiveDate,
+    pub note: Option<String>,
+}
+
+/// One day of a week plan stored in `week_plans`
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PlanEntry {
+    pub commitment_id: i64,
+    pub weekday: i64,
+    pub planned_hours: f64,
+}
+
+/// How much of the weekly target is planned for each day, Monday first whatever day weeks start on
+#[derive(Debug, Clone, Copy)]
+pub struct WeekPlan {
+    pub days: [f64; 7],
+    /// Whether the plan was set for this week rather than split evenly
+    pub custom: bool,
+}
+
+impl WeekPlan {
+    pub fn even(weekly_target_hours: f64) -> Self {
+        WeekPlan {
+            days: [weekly_target_hours / 7.0; 7],
+            custom: false,
+        }
+    }
+
+    /// The plan of `commitment_id` from the week's entries, split evenly if it has none
+    pub fn from_entries(
+        commitment_id: i64,
+        weekly_target_hours: f64,
+        entries: &[PlanEntry],
+    ) -> Self {
+        let mut days = [0.0; 7];
+        let mut custom = false;
+        for e in entries.iter().filter(|e| e.commitment_id == commitment_id) {
+            if let Some(day) = days.get_mut(e.weekday as usize) {
+                *day = e.planned_hours;
+                custom = true;
+            }
+        }
+
+        if custom {
+            WeekPlan { days, custom }
+        } else {
+            WeekPlan::even(weekly_target_hours)
+        }
+    }
+
+    pub fn planned_on(&self, weekday: Weekday) -> f64 {
+        self.days[weekday.num_days_from_monday() as usize]
+    }
+
+    /// Total planned from `start`, the first day of the week, up to and including `weekday`
+    pub fn expected_by(&self, weekday: Weekday, start: Weekday) -> f64 {
+        std::iter::successors(Some(start), |day| Some(day.succ()))
+            .take(weekday.days_since(start) as usize + 1)
+            .map(|day| self.planned_on(day))
+            .fold(0.0, |acc, day| acc + day)
+    }
+
+    pub fn total(&self) -> f64 {
+        self.days.iter().fold(0.0, |acc, day| acc + day)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct WeeklyStat {
     pub week_start: NaiveDate,
     pub total_hours: f64,
+    /// Distinct days with a log in the week
+    pub sessions: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct CumulativePoint {
+    pub week_start: NaiveDate,
+    pub week_hours: f64,
+    pub done: f64,
+    pub required: f64,
+    /// Done minus required with the carry-over policy applied
+    pub balance: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plans_add_up_from_the_start_of_the_week() {
+        let plan = WeekPlan {
+            days: [1.0, 2.0, 0.0, 0.0, 0.0, 3.0, 4.0],
+            custom: true,
+        };
+        assert_eq!(plan.expected_by(Weekday::Tue, Weekday::Mon), 3.0);
+        assert_eq!(plan.expected_by(Weekday::Sun, Weekday::Mon), 10.0);
+        assert_eq!(plan.expected_by(Weekday::Sun, Weekday::Sun), 4.0);
+        assert_eq!(plan.expected_by(Weekday::Mon, Weekday::Sun), 5.0);
+        assert_eq!(plan.expected_by(Weekday::Sat, Weekday::Sun), 10.0);
+    }
+
+    #[test]
+    fn parses_every_carry_over_policy() {
+        assert_eq!("none".parse(), Ok(CarryOver::None));
+        assert_eq!(" Full ".parse(), Ok(CarryOver::Full));
+        assert_eq!("cap:2".parse(), Ok(CarryOver::Capped(2.0)));
+        assert_eq!("capped: 1.5".parse(), Ok(CarryOver::Capped(1.5)));
+        assert_eq!("decay:50%".parse(), Ok(CarryOver::Decay(50.0)));
+        assert_eq!("decay:25".parse(), Ok(CarryOver::Decay(25.0)));
+    }
+
+    #[test]
+    fn rejects_invalid_carry_over_policies() {
+        for policy in [
+            "",
+            "cap",
+            "cap:",
+            "cap:-1",
+            "cap:x",
+            "decay:150",
+            "full:1",
+            "none:0",
+            "half",
+        ] {
+            assert!(
+                policy.parse::<CarryOver>().is_err(),
+                "'{policy}' should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn carry_over_policies_round_trip_through_display() {
+        for policy in [
+            CarryOver::None,
+            CarryOver::Full,
+            CarryOver::Capped(2.5),
+            CarryOver::Decay(50.0),
+        ] {
+            assert_eq!(policy.to_string().parse(), Ok(policy));
+        }
+    }
 }
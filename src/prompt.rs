@@ -0,0 +1,91 @@
+use std::io::{self, IsTerminal};
+
+use chrono::NaiveDate;
+use clap::{CommandFactory, error::ErrorKind};
+use dialoguer::{Confirm, Input, Select, theme::ColorfulTheme};
+
+use crate::{cli::Cli, model::Commitment, unit::Unit, util::monday_of};
+
+/// Prompts are only shown when someone is there to answer them
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal() && io::stderr().is_terminal()
+}
+
+/// Exit with clap's usual error for a missing argument of `subcommand`
+pub fn missing_argument(subcommand: &str, arg: &str) -> ! {
+    let mut cmd = Cli::command().bin_name(env!("CARGO_BIN_NAME"));
+    cmd.build();
+    let sub = cmd
+        .find_subcommand_mut(subcommand)
+        .expect("Unknown subcommand");
+    sub.error(
+        ErrorKind::MissingRequiredArgument,
+        format!(
+            "the following required arguments were not provided:\n  <{}>",
+            arg.to_uppercase()
+        ),
+    )
+    .exit()
+}
+
+/// Let the user pick one of `commitments` from a list, `None` if they cancel with esc or q
+pub fn pick_commitment(
+    prompt: &str,
+    commitments: &[Commitment],
+) -> dialoguer::Result<Option<Commitment>> {
+    let items: Vec<_> = commitments
+        .iter()
+        .map(|c| format!("{} (#{})", c.name, c.id))
+        .collect();
+    let picked = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .items(&items)
+        .default(0)
+        .interact_opt()?;
+
+    Ok(picked.map(|idx| commitments[idx].clone()))
+}
+
+/// Ask a yes or no question, no unless answered otherwise
+pub fn confirm(prompt: &str) -> dialoguer::Result<bool> {
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .default(false)
+        .interact()
+}
+
+pub fn prompt_text(prompt: &str) -> dialoguer::Result<String> {
+    Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .validate_with(|input: &String| {
+            if input.trim().is_empty() {
+                Err("cannot be empty")
+            } else {
+                Ok(())
+            }
+        })
+        .interact_text()
+        .map(|text| text.trim().to_string())
+}
+
+/// Ask for an amount until it parses in `unit`
+pub fn prompt_amount(prompt: &str, unit: &Unit) -> dialoguer::Result<f64> {
+    let text: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("{prompt} ({unit})"))
+        .validate_with(|input: &String| unit.parse_amount(input).map(|_| ()))
+        .interact_text()?;
+
+    Ok(unit
+        .parse_amount(&text)
+        .expect("Amount was validated by the prompt"))
+}
+
+/// Ask for any day of a week, returning its Monday
+pub fn prompt_week(prompt: &str, default: NaiveDate) -> dialoguer::Result<NaiveDate> {
+    let date: NaiveDate = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("{prompt} (YYYY-MM-DD)"))
+        .default(default)
+        .interact_text()?;
+
+    Ok(monday_of(date))
+}
@@ -0,0 +1,234 @@
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use sqlx::SqlitePool;
+
+use crate::{
+    db::{descendant_ids, list_all_commitments, logs_for_week, weekly_stats_for_commitment},
+    model::GoalDirection,
+    stats::{DEFAULT_RECENT_WEEKS, compute_commitment_stats_as_of},
+    unit::Unit,
+    util::{carried_balance, tree_order, weekly_hours_between},
+};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ReportFormat {
+    Md,
+    Html,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReportNote {
+    pub logged_at: NaiveDate,
+    pub amount: f64,
+    pub note: String,
+}
+
+/// One commitment's line in the weekly review
+#[derive(Debug, Clone)]
+pub struct ReportEntry {
+    pub id: i64,
+    pub name: String,
+    /// How deep the commitment is in the project tree, 0 for top-level ones
+    pub depth: usize,
+    pub target: f64,
+    pub direction: GoalDirection,
+    pub unit: Unit,
+    pub actual: f64,
+    pub streak: usize,
+    /// Accumulated done minus required hours up to the end of the reported week
+    pub balance: f64,
+    pub notes: Vec<ReportNote>,
+}
+
+impl ReportEntry {
+    fn display_name(&self) -> String {
+        format!("{}{}", "↳ ".repeat(self.depth), self.name)
+    }
+
+    fn target_text(&self) -> String {
+        let prefix = if self.direction.is_limit() {
+            "≤ "
+        } else {
+            ""
+        };
+        format!("{prefix}{}", self.unit.format(self.target))
+    }
+
+    fn balance_text(&self) -> String {
+        let sign = if self.balance < 0.0 { "-" } else { "+" };
+        format!("{sign}{}", self.unit.format(self.balance.abs()))
+    }
+
+    pub fn pct(&self) -> f64 {
+        if self.target > 0.0 {
+            self.actual / self.target * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Collect the review of the week starting on `week_start`.
+///
+/// Commitments that had not started yet are skipped, and so are archived ones unless something
+/// was logged for them during that week.
+pub async fn build_weekly_report(
+    pool: &SqlitePool,
+    week_start: NaiveDate,
+) -> Result<Vec<ReportEntry>, sqlx::Error> {
+    let logs = logs_for_week(pool, week_start).await?;
+
+    let mut entries = Vec::new();
+    let commitments = list_all_commitments(pool).await?;
+    for (c, depth) in tree_order(commitments, |c| c.id, |c| c.parent_id) {
+        // Logs of sub-commitments count toward their parents
+        let ids = descendant_ids(pool, c.id).await?;
+        let week_logs: Vec<_> = logs
+            .iter()
+            .filter(|l| ids.contains(&l.commitment_id))
+            .collect();
+        if c.start_monday > week_start || (!c.active && week_logs.is_empty()) {
+            continue;
+        }
+
+        let stats = weekly_stats_for_commitment(pool, c.id).await?;
+        let goal = c.goal();
+        let weeks: Vec<_> =
+            weekly_hours_between(c.start_monday, goal.last_monday(week_start), &stats)
+                .into_iter()
+                .filter(|(week, _)| goal.counts_week(*week))
+                .collect();
+        let streak =
+            compute_commitment_stats_as_of(&goal, &stats, DEFAULT_RECENT_WEEKS, week_start)
+                .current_streak;
+
+        entries.push(ReportEntry {
+            id: c.id,
+            unit: c.unit(),
+            name: c.name,
+            depth,
+            target: c.weekly_target_hours,
+            direction: c.goal_direction,
+            actual: week_logs.iter().fold(0.0, |acc, l| acc + l.hours),
+            streak,
+            balance: carried_balance(&goal, &weeks),
+            notes: week_logs
+                .iter()
+                .filter_map(|l| {
+                    l.note.as_ref().map(|note| ReportNote {
+                        logged_at: l.logged_at,
+                        amount: l.hours,
+                        note: note.clone(),
+                    })
+                })
+                .collect(),
+        });
+    }
+
+    Ok(entries)
+}
+
+pub fn render_report(
+    format: ReportFormat,
+    week_start: NaiveDate,
+    entries: &[ReportEntry],
+) -> String {
+    match format {
+        ReportFormat::Md => render_markdown(week_start, entries),
+        ReportFormat::Html => render_html(week_start, entries),
+    }
+}
+
+fn escape_markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_markdown(week_start: NaiveDate, entries: &[ReportEntry]) -> String {
+    let mut out = format!(
+        "# Weekly Review: week of {}\n\n",
+        week_start.format("%Y-%m-%d")
+    );
+
+    if entries.is_empty() {
+        out.push_str("No commitments for this week.\n");
+        return out;
+    }
+
+    out.push_str("| Commitment | Target | Actual | % | Streak | Balance |\n");
+    out.push_str("|---|---:|---:|---:|---:|---:|\n");
+    for e in entries {
+        out.push_str(&format!(
+            "| {} (#{}) | {} | {} | {:.0}% | {} | {} |\n",
+            escape_markdown_cell(&e.display_name()),
+            e.id,
+            escape_markdown_cell(&e.target_text()),
+            escape_markdown_cell(&e.unit.format(e.actual)),
+            e.pct(),
+            e.streak,
+            escape_markdown_cell(&e.balance_text())
+        ));
+    }
+
+    for e in entries.iter().filter(|e| !e.notes.is_empty()) {
+        out.push_str(&format!("\n## {}\n\n", e.name));
+        for n in &e.notes {
+            out.push_str(&format!(
+                "- {} ({}): {}\n",
+                n.logged_at.format("%a %Y-%m-%d"),
+                e.unit.format(n.amount),
+                n.note
+            ));
+        }
+    }
+
+    out
+}
+
+fn render_html(week_start: NaiveDate, entries: &[ReportEntry]) -> String {
+    let title = format!("Weekly Review: week of {}", week_start.format("%Y-%m-%d"));
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n<body>\n<h1>{title}</h1>\n"
+    );
+
+    if entries.is_empty() {
+        out.push_str("<p>No commitments for this week.</p>\n");
+    } else {
+        out.push_str("<table>\n<thead>\n<tr><th>Commitment</th><th>Target</th><th>Actual</th><th>%</th><th>Streak</th><th>Balance</th></tr>\n</thead>\n<tbody>\n");
+        for e in entries {
+            out.push_str(&format!(
+                "<tr><td>{} (#{})</td><td>{}</td><td>{}</td><td>{:.0}%</td><td>{}</td><td>{}</td></tr>\n",
+                escape_html(&e.display_name()),
+                e.id,
+                escape_html(&e.target_text()),
+                escape_html(&e.unit.format(e.actual)),
+                e.pct(),
+                e.streak,
+                escape_html(&e.balance_text())
+            ));
+        }
+        out.push_str("</tbody>\n</table>\n");
+
+        for e in entries.iter().filter(|e| !e.notes.is_empty()) {
+            out.push_str(&format!("<h2>{}</h2>\n<ul>\n", escape_html(&e.name)));
+            for n in &e.notes {
+                out.push_str(&format!(
+                    "<li>{} ({}): {}</li>\n",
+                    n.logged_at.format("%a %Y-%m-%d"),
+                    escape_html(&e.unit.format(n.amount)),
+                    escape_html(&n.note)
+                ));
+            }
+            out.push_str("</ul>\n");
+        }
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
@@ -0,0 +1,174 @@
+use std::{fmt, str::FromStr};
+
+use sqlx::SqlitePool;
+
+use crate::{db::list_all_commitments, model::Commitment};
+
+/// How many names are suggested when a selector doesn't match anything
+const MAX_SUGGESTIONS: usize = 3;
+
+/// A commitment picked on the command line by id, name or the start of its name
+#[derive(Debug, Clone)]
+pub struct Selector(String);
+
+impl FromStr for Selector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err("commitment cannot be empty".to_string());
+        }
+        Ok(Selector(s.to_string()))
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Why a selector could not be resolved to a single commitment
+#[derive(Debug, Clone)]
+pub enum SelectError {
+    /// Nothing matches, with the closest names as suggestions
+    NotFound {
+        selector: String,
+        suggestions: Vec<(i64, String)>,
+    },
+    /// Several commitments match equally well
+    Ambiguous {
+        selector: String,
+        candidates: Vec<(i64, String)>,
+    },
+}
+
+fn format_candidates(candidates: &[(i64, String)]) -> String {
+    candidates
+        .iter()
+        .map(|(id, name)| format!("'{name}' (#{id})"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl fmt::Display for SelectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectError::NotFound {
+                selector,
+                suggestions,
+            } => {
+                write!(f, "Cannot find commitment '{selector}'.")?;
+                if !suggestions.is_empty() {
+                    write!(f, " Did you mean {}?", format_candidates(suggestions))?;
+                }
+                Ok(())
+            }
+            SelectError::Ambiguous {
+                selector,
+                candidates,
+            } => write!(
+                f,
+                "'{selector}' matches several commitments: {}. Use the id or more of the name.",
+                format_candidates(candidates)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SelectError {}
+
+impl Selector {
+    /// The preset name of an `@name` selector
+    pub fn preset_name(&self) -> Option<&str> {
+        self.0.strip_prefix('@')
+    }
+
+    /// Pick the commitment this selector refers to.
+    ///
+    /// Tries, in order, an id (`3` or `#3`), the exact name, the name ignoring case and finally
+    /// the start of a name. When a step matches several commitments, an only active one wins.
+    pub fn resolve(&self, commitments: &[Commitment]) -> Result<Commitment, SelectError> {
+        let query = self.0.as_str();
+        if let Ok(id) = query.trim_start_matches('#').parse::<i64>()
+            && let Some(c) = commitments.iter().find(|c| c.id == id)
+        {
+            return Ok(c.clone());
+        }
+
+        let lower = query.to_lowercase();
+        let steps: [&dyn Fn(&Commitment) -> bool; 3] = [
+            &|c| c.name == query,
+            &|c| c.name.to_lowercase() == lower,
+            &|c| c.name.to_lowercase().starts_with(&lower),
+        ];
+        for step in steps {
+            let matches: Vec<_> = commitments.iter().filter(|c| step(c)).collect();
+            let active: Vec<_> = matches.iter().copied().filter(|c| c.active).collect();
+            match (matches.as_slice(), active.as_slice()) {
+                ([], _) => continue,
+                ([c], _) | (_, [c]) => return Ok((*c).clone()),
+                _ => {
+                    return Err(SelectError::Ambiguous {
+                        selector: query.to_string(),
+                        candidates: matches.iter().map(|c| (c.id, c.name.clone())).collect(),
+                    });
+                }
+            }
+        }
+
+        Err(SelectError::NotFound {
+            selector: query.to_string(),
+            suggestions: suggestions(&lower, commitments),
+        })
+    }
+}
+
+/// Names that contain the query or are only a couple of typos away from it, closest first
+fn suggestions(lower: &str, commitments: &[Commitment]) -> Vec<(i64, String)> {
+    let max_distance = (lower.chars().count() / 3).max(1);
+    let mut close: Vec<_> = commitments
+        .iter()
+        .filter_map(|c| {
+            let name = c.name.to_lowercase();
+            let distance = if name.contains(lower) {
+                0
+            } else {
+                edit_distance(lower, &name)
+            };
+            (distance <= max_distance).then_some((distance, c))
+        })
+        .collect();
+    close.sort_by_key(|(distance, c)| (*distance, !c.active, c.id));
+
+    close
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, c)| (c.id, c.name.clone()))
+        .collect()
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
+/// Resolve a selector against every commitment, archived ones included
+pub async fn select_commitment(
+    pool: &SqlitePool,
+    selector: &Selector,
+) -> Result<Result<Commitment, SelectError>, sqlx::Error> {
+    let commitments = list_all_commitments(pool).await?;
+    Ok(selector.resolve(&commitments))
+}
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::{
+    model::{Goal, Milestone, WeeklyStat},
+    util::{get_monday_this_week, monday_of, week_start, weekly_hours_between},
+};
+
+/// Number of recent weeks used for the hit rate when nothing else is asked for
+pub const DEFAULT_RECENT_WEEKS: usize = 8;
+
+#[derive(Debug, Clone)]
+pub struct CommitmentStats {
+    /// Consecutive weeks meeting target, ending at the latest finished week.
+    ///
+    /// The current week only extends the streak once its target is met, it never breaks it.
+    pub current_streak: usize,
+    pub longest_streak: usize,
+    pub recent_weeks: usize,
+    pub recent_hits: usize,
+    pub average_hours: f64,
+    pub median_hours: f64,
+    pub best_week: Option<(NaiveDate, f64)>,
+    pub worst_week: Option<(NaiveDate, f64)>,
+}
+
+impl CommitmentStats {
+    /// Percentage of the recent weeks that met target
+    pub fn hit_rate(&self) -> f64 {
+        if self.recent_weeks == 0 {
+            0.0
+        } else {
+            self.recent_hits as f64 / self.recent_weeks as f64 * 100.0
+        }
+    }
+}
+
+fn median(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+/// Compute the streak and consistency statistics of a commitment.
+///
+/// Only finished weeks are used for averages and best/worst weeks, as the current one would
+/// otherwise always look like the worst. Before the first week is over, the current week is used.
+pub fn compute_commitment_stats(
+    goal: &Goal,
+    stats: &[WeeklyStat],
+    recent_weeks: usize,
+) -> CommitmentStats {
+    compute_commitment_stats_as_of(goal, stats, recent_weeks, get_monday_this_week())
+}
+
+/// Same as [`compute_commitment_stats()`], but treating the week of `this_monday` as the current
+/// one and ignoring everything after it.
+pub fn compute_commitment_stats_as_of(
+    goal: &Goal,
+    stats: &[WeeklyStat],
+    recent_weeks: usize,
+    this_monday: NaiveDate,
+) -> CommitmentStats {
+    let sessions_by_week: HashMap<NaiveDate, i64> =
+        stats.iter().map(|s| (s.week_start, s.sessions)).collect();
+    let meets_target = |(week, hours): &(NaiveDate, f64)| {
+        goal.is_met(*hours, sessions_by_week.get(week).copied().unwrap_or(0))
+    };
+    let weeks: Vec<_> =
+        weekly_hours_between(goal.start_monday, goal.last_monday(this_monday), stats)
+            .into_iter()
+            .filter(|(week, _)| goal.counts_week(*week))
+            .collect();
+
+    let (finished, current): (Vec<_>, Vec<_>) = weeks
+        .iter()
+        .copied()
+        .partition(|(week, _)| *week < this_monday);
+    // An unfinished week can still go over a limit, so it only counts for minimum targets
+    let current_met = !goal.direction.is_limit() && current.first().is_some_and(meets_target);
+
+    let mut longest_streak = 0;
+    let mut running = 0;
+    for week in &finished {
+        if meets_target(week) {
+            running += 1;
+            longest_streak = longest_streak.max(running);
+        } else {
+            running = 0;
+        }
+    }
+    let current_streak = running + usize::from(current_met);
+    let longest_streak = longest_streak.max(current_streak);
+
+    let recent: Vec<_> = finished.iter().rev().take(recent_weeks).collect();
+    let recent_hits = recent.iter().filter(|week| meets_target(week)).count();
+
+    let sample = if finished.is_empty() {
+        &current
+    } else {
+        &finished
+    };
+    let hours: Vec<f64> = sample.iter().map(|(_, hours)| *hours).collect();
+    let average_hours = if hours.is_empty() {
+        0.0
+    } else {
+        hours.iter().sum::<f64>() / hours.len() as f64
+    };
+
+    let most = sample.iter().copied().max_by(|a, b| a.1.total_cmp(&b.1));
+    let least = sample.iter().copied().min_by(|a, b| a.1.total_cmp(&b.1));
+    let (best_week, worst_week) = if goal.direction.is_limit() {
+        (least, most)
+    } else {
+        (most, least)
+    };
+
+    CommitmentStats {
+        current_streak,
+        longest_streak,
+        recent_weeks: recent.len(),
+        recent_hits,
+        average_hours,
+        median_hours: median(&hours),
+        best_week,
+        worst_week,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WeekForecast {
+    /// Days left in the week, including today
+    pub days_left: i64,
+    /// Hours per day needed for the rest of the week to reach target, or still allowed per day
+    /// for limits
+    pub required_daily: f64,
+    /// End of week total if the pace so far is kept up
+    pub projected_total: f64,
+}
+
+/// Forecast the end of the current week from the hours done so far.
+pub fn forecast_week(done: f64, weekly_target_hours: f64, today: NaiveDate) -> WeekForecast {
+    let days_elapsed = today.weekday().days_since(week_start()) as i64 + 1;
+    let days_left = 7 - days_elapsed + 1;
+
+    WeekForecast {
+        days_left,
+        required_daily: (weekly_target_hours - done).max(0.0) / days_left as f64,
+        projected_total: done / days_elapsed as f64 * 7.0,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MilestoneProgress {
+    pub milestone: Milestone,
+    /// Hours done since the commitment started
+    pub done: f64,
+    pub days_left: i64,
+    /// Hours per week needed from now on to reach the total by the due date
+    pub required_weekly: f64,
+    /// When the total is reached at the average weekly pace so far, `None` without any progress
+    pub projected_completion: Option<NaiveDate>,
+}
+
+impl MilestoneProgress {
+    pub fn remaining(&self) -> f64 {
+        (self.milestone.total - self.done).max(0.0)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.remaining() <= 0.0
+    }
+
+    /// Whether the total is reached, or projected to be reached, by the due date
+    pub fn on_track(&self) -> bool {
+        self.is_complete()
+            || self
+                .projected_completion
+                .is_some_and(|date| date <= self.milestone.due)
+    }
+}
+
+/// Progress toward the milestone of `goal` as of `today`, if it has one.
+pub fn milestone_progress(
+    goal: &Goal,
+    stats: &[WeeklyStat],
+    today: NaiveDate,
+) -> Option<MilestoneProgress> {
+    let milestone = goal.milestone?;
+    let done = weekly_hours_between(goal.start_monday, goal.last_monday(monday_of(today)), stats)
+        .iter()
+        .fold(0.0, |acc, (_, hours)| acc + hours);
+    let remaining = (milestone.total - done).max(0.0);
+
+    let days_left = (milestone.due - today).num_days();
+    // Today still counts, so a milestone due today leaves one day
+    let weeks_left = (days_left + 1).max(1) as f64 / 7.0;
+    let days_elapsed = (today - goal.start_monday).num_days() + 1;
+    let daily_pace = if days_elapsed > 0 {
+        done / days_elapsed as f64
+    } else {
+        0.0
+    };
+    let projected_completion = if remaining <= 0.0 {
+        Some(today)
+    } else if daily_pace > 0.0 {
+        // Far enough in the future to not fit in a date is as good as never
+        Duration::try_days((remaining / daily_pace).ceil() as i64)
+            .and_then(|days| today.checked_add_signed(days))
+    } else {
+        None
+    };
+
+    Some(MilestoneProgress {
+        milestone,
+        done,
+        days_left,
+        required_weekly: remaining / weeks_left,
+        projected_completion,
+    })
+}
@@ -0,0 +1,246 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use anyhow::{Context, bail};
+use ratatui::style::{Color, Style, Stylize, palette::tailwind};
+use serde::{Deserialize, Serialize};
+
+/// Names of the themes that are always available
+pub const BUILTIN_THEMES: [&str; 3] = ["dark", "light", "high-contrast"];
+
+/// Whether colours are turned off with a non-empty `NO_COLOR`, see https://no-color.org
+pub fn no_color() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+/// The colours of the TUI, by what they are used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Separators, hints, archived commitments and other less important text
+    pub muted: Color,
+    /// Titles and the done line of the cumulative chart
+    pub title: Color,
+    pub title_bg: Color,
+    /// The current week and preset names
+    pub accent: Color,
+    pub good: Color,
+    pub warn: Color,
+    pub bad: Color,
+    /// Gauges of targets reached this week
+    pub reached: Color,
+    /// Gauges of targets still in progress
+    pub progress: Color,
+    pub selection: Color,
+    pub selection_bg: Color,
+    /// The message in the footer
+    pub message: Color,
+    /// The key hints in the footer
+    pub hint: Color,
+    pub mode_normal: Color,
+    pub mode_log: Color,
+    pub mode_create: Color,
+    pub mode_delete: Color,
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            muted: tailwind::GRAY.c600,
+            title: tailwind::ROSE.c500,
+            title_bg: tailwind::STONE.c900,
+            accent: tailwind::CYAN.c400,
+            good: tailwind::EMERALD.c500,
+            warn: tailwind::AMBER.c500,
+            bad: tailwind::RED.c500,
+            reached: tailwind::CYAN.c600,
+            progress: tailwind::GREEN.c700,
+            selection: Color::White,
+            selection_bg: tailwind::BLUE.c500,
+            message: tailwind::RED.c300,
+            hint: tailwind::GRAY.c100,
+            mode_normal: tailwind::PURPLE.c800,
+            mode_log: tailwind::CYAN.c800,
+            mode_create: tailwind::EMERALD.c800,
+            mode_delete: tailwind::RED.c800,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            muted: tailwind::SLATE.c500,
+            title: tailwind::ROSE.c700,
+            title_bg: tailwind::STONE.c100,
+            accent: tailwind::CYAN.c700,
+            good: tailwind::EMERALD.c700,
+            warn: tailwind::AMBER.c700,
+            bad: tailwind::RED.c700,
+            reached: tailwind::CYAN.c700,
+            progress: tailwind::GREEN.c600,
+            selection: Color::White,
+            selection_bg: tailwind::BLUE.c600,
+            message: tailwind::RED.c700,
+            hint: tailwind::SLATE.c700,
+            mode_normal: tailwind::PURPLE.c300,
+            mode_log: tailwind::CYAN.c300,
+            mode_create: tailwind::EMERALD.c300,
+            mode_delete: tailwind::RED.c300,
+        }
+    }
+
+    /// Plain terminal colours, which follow the terminal's own palette
+    pub fn high_contrast() -> Self {
+        Self {
+            muted: Color::Gray,
+            title: Color::LightYellow,
+            title_bg: Color::Black,
+            accent: Color::LightCyan,
+            good: Color::LightGreen,
+            warn: Color::LightYellow,
+            bad: Color::LightRed,
+            reached: Color::LightCyan,
+            progress: Color::LightGreen,
+            selection: Color::Black,
+            selection_bg: Color::White,
+            message: Color::LightYellow,
+            hint: Color::White,
+            mode_normal: Color::Magenta,
+            mode_log: Color::Cyan,
+            mode_create: Color::Green,
+            mode_delete: Color::Red,
+        }
+    }
+
+    /// No colours at all, for `NO_COLOR`
+    pub fn monochrome() -> Self {
+        Self {
+            muted: Color::Reset,
+            title: Color::Reset,
+            title_bg: Color::Reset,
+            accent: Color::Reset,
+            good: Color::Reset,
+            warn: Color::Reset,
+            bad: Color::Reset,
+            reached: Color::Reset,
+            progress: Color::Reset,
+            selection: Color::Reset,
+            selection_bg: Color::Reset,
+            message: Color::Reset,
+            hint: Color::Reset,
+            mode_normal: Color::Reset,
+            mode_log: Color::Reset,
+            mode_create: Color::Reset,
+            mode_delete: Color::Reset,
+        }
+    }
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// The theme called `name`, either built in or defined in `themes`
+    pub fn named(name: &str, themes: &BTreeMap<String, CustomTheme>) -> anyhow::Result<Self> {
+        if let Some(custom) = themes.get(name) {
+            return custom.build().with_context(|| format!("in themes.{name}"));
+        }
+        match Self::builtin(name) {
+            Some(theme) => Ok(theme),
+            None => bail!(
+                "unknown theme '{name}', expected one of {} or a [themes.<name>] table",
+                BUILTIN_THEMES.join(", ")
+            ),
+        }
+    }
+
+    /// A background that is still visible without colours, by reversing the text instead
+    pub fn bg(&self, color: Color) -> Style {
+        if color == Color::Reset {
+            Style::default().reversed()
+        } else {
+            Style::default().bg(color)
+        }
+    }
+
+    /// The selected row of a list
+    pub fn selected(&self) -> Style {
+        self.bg(self.selection_bg).fg(self.selection)
+    }
+}
+
+/// A theme in the config file, any colour left out comes from the `base` theme:
+///
+/// ```toml
+/// [themes.mine]
+/// base = "light"
+/// good = "#2e7d32"
+/// bad = "red"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CustomTheme {
+    /// One of the built-in themes, `dark` when left out
+    pub base: Option<String>,
+    pub muted: Option<String>,
+    pub title: Option<String>,
+    pub title_bg: Option<String>,
+    pub accent: Option<String>,
+    pub good: Option<String>,
+    pub warn: Option<String>,
+    pub bad: Option<String>,
+    pub reached: Option<String>,
+    pub progress: Option<String>,
+    pub selection: Option<String>,
+    pub selection_bg: Option<String>,
+    pub message: Option<String>,
+    pub hint: Option<String>,
+    pub mode_normal: Option<String>,
+    pub mode_log: Option<String>,
+    pub mode_create: Option<String>,
+    pub mode_delete: Option<String>,
+}
+
+/// Replace `color` with the parsed `value` when there is one
+fn set_color(color: &mut Color, key: &str, value: &Option<String>) -> anyhow::Result<()> {
+    if let Some(value) = value {
+        *color = Color::from_str(value).map_err(|_| {
+            anyhow::anyhow!(
+                "{key} = '{value}' is not a colour, use a name such as 'red' or '#rrggbb'"
+            )
+        })?;
+    }
+    Ok(())
+}
+
+impl CustomTheme {
+    pub fn build(&self) -> anyhow::Result<Theme> {
+        let base = self.base.as_deref().unwrap_or("dark");
+        let Some(mut theme) = Theme::builtin(base) else {
+            bail!(
+                "base = '{base}' is not a built-in theme, expected one of {}",
+                BUILTIN_THEMES.join(", ")
+            );
+        };
+
+        set_color(&mut theme.muted, "muted", &self.muted)?;
+        set_color(&mut theme.title, "title", &self.title)?;
+        set_color(&mut theme.title_bg, "title_bg", &self.title_bg)?;
+        set_color(&mut theme.accent, "accent", &self.accent)?;
+        set_color(&mut theme.good, "good", &self.good)?;
+        set_color(&mut theme.warn, "warn", &self.warn)?;
+        set_color(&mut theme.bad, "bad", &self.bad)?;
+        set_color(&mut theme.reached, "reached", &self.reached)?;
+        set_color(&mut theme.progress, "progress", &self.progress)?;
+        set_color(&mut theme.selection, "selection", &self.selection)?;
+        set_color(&mut theme.selection_bg, "selection_bg", &self.selection_bg)?;
+        set_color(&mut theme.message, "message", &self.message)?;
+        set_color(&mut theme.hint, "hint", &self.hint)?;
+        set_color(&mut theme.mode_normal, "mode_normal", &self.mode_normal)?;
+        set_color(&mut theme.mode_log, "mode_log", &self.mode_log)?;
+        set_color(&mut theme.mode_create, "mode_create", &self.mode_create)?;
+        set_color(&mut theme.mode_delete, "mode_delete", &self.mode_delete)?;
+        Ok(theme)
+    }
+}
@@ -4,7 +4,10 @@ use std::{
 };
 
 use crossterm::{
-    event::{self, Event, KeyCode, KeyModifiers},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, MouseButton,
+        MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
@@ -12,22 +15,47 @@ use ratatui::{Terminal, prelude::CrosstermBackend};
 use sqlx::SqlitePool;
 
 use crate::{
-    app::{App, CreateCommitmentInputField, InputMode},
+    app::{App, Button, ClickTarget, CreateCommitmentInputField, InputMode, ListFilter},
+    config::Config,
     db::log_record_id,
+    keymap::Action,
+    util::get_monday_this_week,
 };
 
-pub async fn run_tui(pool: SqlitePool) -> anyhow::Result<()> {
+/// Number of weeks scrolled at once when paging through the weekly chart
+const CHART_PAGE_WEEKS: usize = 8;
+
+pub async fn run_tui(pool: SqlitePool, config: &Config) -> anyhow::Result<()> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new(pool).await?;
-    let tick_rate = Duration::from_millis(20);
+    // Restore the terminal even when the app fails, or the shell is left printing mouse events
+    let res = run_app(&mut terminal, pool, config).await;
+
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
+
+    res
+}
+
+async fn run_app(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    pool: SqlitePool,
+    config: &Config,
+) -> anyhow::Result<()> {
+    let mut app = App::new(pool, config).await?;
+    let tick_rate = Duration::from_millis(config.tui.tick_ms);
     let mut last_tick = Instant::now();
 
-    let res = loop {
+    loop {
         terminal.draw(|f| crate::ui::draw(f, &mut app))?;
 
         let timeout = tick_rate
@@ -35,23 +63,20 @@ pub async fn run_tui(pool: SqlitePool) -> anyhow::Result<()> {
             .unwrap_or_else(|| Duration::from_secs(0));
 
         if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if handle_key_event(key, &mut app).await? {
-                    break Ok(());
-                }
+            let quit = match event::read()? {
+                Event::Key(key) => handle_key_event(key, &mut app).await?,
+                Event::Mouse(mouse) => handle_mouse_event(mouse, &mut app).await?,
+                _ => false,
+            };
+            if quit {
+                return Ok(());
             }
         }
 
         if last_tick.elapsed() >= tick_rate {
             last_tick = Instant::now();
         }
-    };
-
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
-
-    res
+    }
 }
 
 async fn handle_key_event(key: event::KeyEvent, app: &mut App) -> anyhow::Result<bool> {
@@ -62,48 +87,224 @@ async fn handle_key_event(key: event::KeyEvent, app: &mut App) -> anyhow::Result
         InputMode::Normal => handle_normal_mode(key, app).await,
         InputMode::LogHours => handle_log_hour_mode(key, app).await,
         InputMode::CreateCommitment(_) => handle_edit_commitment_mode(key, app).await,
+        InputMode::ConfirmDelete(_) => handle_confirm_delete_mode(key, app).await,
+        InputMode::PickPreset => handle_pick_preset_mode(key, app).await,
+        InputMode::Help => handle_help_mode(key, app).await,
+        InputMode::WeekLogs(_) => handle_week_logs_mode(key, app).await,
+        InputMode::Search => handle_search_mode(key, app).await,
+    }
+}
+
+async fn handle_mouse_event(mouse: MouseEvent, app: &mut App) -> anyhow::Result<bool> {
+    let target = app.click_target_at(mouse.column, mouse.row);
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            let Some(target) = target else {
+                return Ok(false);
+            };
+            return handle_click(target, app).await;
+        }
+        MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {
+            let down = mouse.kind == MouseEventKind::ScrollDown;
+            match (&app.input_mode, target) {
+                (InputMode::Normal, Some(ClickTarget::List | ClickTarget::Row(_))) => {
+                    if down {
+                        app.next();
+                    } else {
+                        app.previous();
+                    }
+                }
+                // Scrolling down moves towards the current week, like the arrow keys
+                (InputMode::Normal, Some(ClickTarget::Chart | ClickTarget::Week(_))) => {
+                    if down {
+                        app.scroll_chart_newer(1);
+                    } else {
+                        app.scroll_chart_older(1);
+                    }
+                }
+                (InputMode::PickPreset, _) => {
+                    if down {
+                        app.next_preset();
+                    } else {
+                        app.previous_preset();
+                    }
+                }
+                (InputMode::Help, _) => {
+                    app.help_scroll = if down {
+                        app.help_scroll.saturating_add(1)
+                    } else {
+                        app.help_scroll.saturating_sub(1)
+                    };
+                }
+                _ => {}
+            }
+        }
+        _ => {}
     }
+
+    Ok(false)
+}
+
+/// React to a left click, only on the targets that belong to the current mode
+async fn handle_click(target: ClickTarget, app: &mut App) -> anyhow::Result<bool> {
+    match (&app.input_mode, target) {
+        (InputMode::Normal, ClickTarget::Row(idx)) => {
+            app.pending_keys.clear();
+            app.jump_to_row(idx + 1);
+        }
+        (InputMode::Normal, ClickTarget::Week(week)) => {
+            app.pending_keys.clear();
+            app.show_week_logs(week).await?;
+        }
+        (InputMode::Normal, ClickTarget::Action(action)) => {
+            app.pending_keys.clear();
+            return run_action(action, None, app).await;
+        }
+        (InputMode::LogHours, ClickTarget::Button(Button::Log)) => {
+            submit_log(app).await?;
+        }
+        (InputMode::ConfirmDelete(_), ClickTarget::Button(Button::Delete)) => {
+            app.confirm_delete().await?;
+        }
+        (InputMode::ConfirmDelete(_), ClickTarget::Button(Button::Cancel)) => {
+            app.switch_input_mode(InputMode::Normal);
+            app.set_message("Nothing was deleted");
+        }
+        (InputMode::PickPreset, ClickTarget::Preset(idx)) => {
+            app.log_preset(idx).await?;
+        }
+        (
+            InputMode::LogHours | InputMode::PickPreset | InputMode::Help | InputMode::WeekLogs(_),
+            ClickTarget::Button(Button::Cancel | Button::Close),
+        ) => {
+            app.switch_input_mode(InputMode::Normal);
+        }
+        _ => {}
+    }
+
+    Ok(false)
 }
 
 async fn handle_normal_mode(key: event::KeyEvent, app: &mut App) -> Result<bool, anyhow::Error> {
-    match key.code {
-        KeyCode::Char('q') => {
+    let Some((action, count)) = app.press_key(key) else {
+        return Ok(false);
+    };
+    run_action(action, count, app).await
+}
+
+/// Run an action bound to a key or clicked in the footer, returning whether to quit
+async fn run_action(
+    action: Action,
+    count: Option<usize>,
+    app: &mut App,
+) -> Result<bool, anyhow::Error> {
+    // Repeated actions such as `5j` run `count` times, the others ignore it
+    let times = count.unwrap_or(1);
+
+    match action {
+        Action::Quit => {
             return Ok(true);
         }
-        KeyCode::Down | KeyCode::Char('j') => {
-            app.next();
+        Action::Help => {
+            app.open_help();
         }
-        KeyCode::Up | KeyCode::Char('k') => {
-            app.previous();
+        Action::Down => {
+            for _ in 0..times {
+                app.next();
+            }
+        }
+        Action::Up => {
+            for _ in 0..times {
+                app.previous();
+            }
+        }
+        Action::First => match count {
+            Some(row) => app.jump_to_row(row),
+            None => app.jump_first(),
+        },
+        Action::Last => match count {
+            Some(row) => app.jump_to_row(row),
+            None => app.jump_last(),
+        },
+        Action::Fold => {
+            app.toggle_fold_selected();
+        }
+        Action::ToggleView => {
+            app.toggle_detail_view();
         }
-        KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            app.jump_first();
+        Action::Older => {
+            app.scroll_chart_older(times);
         }
-        KeyCode::Char('G') => {
-            app.jump_last();
+        Action::Newer => {
+            app.scroll_chart_newer(times);
         }
-        KeyCode::Char('r') => {
-            app.reactivate_selected().await?;
+        Action::PageOlder => {
+            app.scroll_chart_older(CHART_PAGE_WEEKS * times);
         }
-        KeyCode::Char('a') => {
-            app.archive_selected().await?;
+        Action::PageNewer => {
+            app.scroll_chart_newer(CHART_PAGE_WEEKS * times);
+        }
+        Action::ZoomIn => {
+            for _ in 0..times {
+                app.zoom_chart_in();
+            }
         }
-        KeyCode::Char('l') => {
+        Action::ZoomOut => {
+            for _ in 0..times {
+                app.zoom_chart_out();
+            }
+        }
+        Action::Reactivate => {
+            app.reactivate_selected(false).await?;
+        }
+        Action::ReactivateFresh => {
+            app.reactivate_selected(true).await?;
+        }
+        Action::Archive => {
+            app.archive_selected(times).await?;
+        }
+        Action::Delete => {
+            app.request_delete_selected().await?;
+        }
+        Action::Log => {
             if let Some(sel) = app.get_selected_item() {
-                if sel.0.active {
+                if sel.0.commitment.active {
                     app.switch_input_mode(InputMode::LogHours);
                 } else {
                     app.set_message("You can only log hours for activated items");
                 }
             }
         }
-        KeyCode::Char('c') => {
+        Action::Presets => {
+            app.open_preset_picker();
+        }
+        Action::Search => {
+            app.start_search();
+        }
+        Action::NextMatch => {
+            app.next_match(times);
+        }
+        Action::PreviousMatch => {
+            app.previous_match(times);
+        }
+        Action::FilterActive => {
+            app.toggle_filter(ListFilter::Active);
+        }
+        Action::FilterArchived => {
+            app.toggle_filter(ListFilter::Archived);
+        }
+        Action::FilterBehind => {
+            app.toggle_filter(ListFilter::Behind);
+        }
+        Action::ClearFilter => {
+            app.clear_filter();
+        }
+        Action::Create => {
             app.set_message("New commitment name: (Enter to confirm, ESC to cancel)");
             app.switch_input_mode(InputMode::CreateCommitment(
                 CreateCommitmentInputField::Name,
             ));
         }
-        _ => {}
     };
 
     Ok(false)
@@ -117,41 +318,51 @@ async fn handle_log_hour_mode(key: event::KeyEvent, app: &mut App) -> Result<boo
         KeyCode::Char(value) => {
             if value == 'u' && key.modifiers.contains(KeyModifiers::CONTROL) {
                 app.input_buffer.clear();
-            }
-
-            if value.is_numeric() || value == '.' {
+            } else if value.is_alphanumeric() || value == '.' || value == ' ' {
+                // Letters are allowed for amounts such as `1h30m` or `5km`
                 app.input_buffer.push(value);
-                if !app.input_buffer.is_empty() && app.input_buffer.parse::<f32>().is_err() {
-                    app.set_message("Please input numeric values.");
-                    app.input_buffer.pop();
-                }
             } else {
-                app.set_message("Please input numeric values.");
+                app.set_message("Please input an amount such as 1.5, 45m or 5km.");
             }
         }
         KeyCode::Backspace => {
             app.input_buffer.pop();
         }
+        KeyCode::Tab => {
+            app.open_preset_picker();
+        }
         KeyCode::Enter => {
-            app.set_message("Your request is being processed...");
+            submit_log(app).await?;
+        }
+        _ => {}
+    }
 
-            let id = app.get_selected_item().unwrap().0.id;
+    Ok(false)
+}
 
-            let log_result =
-                log_record_id(app.get_pool(), id, app.input_buffer.parse::<f32>().unwrap()).await;
+async fn handle_edit_commitment_mode(
+    key: event::KeyEvent,
+    app: &mut App,
+) -> Result<bool, anyhow::Error> {
+    if key.code == KeyCode::Esc {
+        app.switch_input_mode(InputMode::Normal);
+        app.set_message("Going back to Normal mode...");
+    }
 
-            match log_result {
-                Ok(id) => {
-                    app.set_message(format!("Logging to #{id} is successful."));
-                    app.mark_dirty(true);
-                    app.switch_input_mode(InputMode::Normal);
-                }
-                Err(e) => {
-                    app.set_message(format!("Logging to #{id} is unsuccessful because {e}."));
-                }
-            };
+    Ok(false)
+}
 
-            app.refresh_from_db_if_dirty().await?;
+async fn handle_confirm_delete_mode(
+    key: event::KeyEvent,
+    app: &mut App,
+) -> Result<bool, anyhow::Error> {
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Char('Y') => {
+            app.confirm_delete().await?;
+        }
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+            app.switch_input_mode(InputMode::Normal);
+            app.set_message("Nothing was deleted");
         }
         _ => {}
     }
@@ -159,14 +370,131 @@ async fn handle_log_hour_mode(key: event::KeyEvent, app: &mut App) -> Result<boo
     Ok(false)
 }
 
-async fn handle_edit_commitment_mode(
+async fn handle_pick_preset_mode(
     key: event::KeyEvent,
     app: &mut App,
 ) -> Result<bool, anyhow::Error> {
     match key.code {
         KeyCode::Esc => {
             app.switch_input_mode(InputMode::Normal);
-            app.set_message("Going back to Normal mode...");
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            app.next_preset();
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.previous_preset();
+        }
+        KeyCode::Char(digit @ '1'..='9') => {
+            let idx = digit as usize - '1' as usize;
+            app.log_preset(idx).await?;
+        }
+        KeyCode::Enter => {
+            if let Some(idx) = app.preset_state.selected() {
+                app.log_preset(idx).await?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(false)
+}
+
+async fn handle_help_mode(key: event::KeyEvent, app: &mut App) -> Result<bool, anyhow::Error> {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') | KeyCode::Enter => {
+            app.switch_input_mode(InputMode::Normal);
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            app.help_scroll = app.help_scroll.saturating_add(1);
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.help_scroll = app.help_scroll.saturating_sub(1);
+        }
+        _ => {}
+    }
+
+    Ok(false)
+}
+
+/// Log the amount typed in the log overlay to the selected commitment
+async fn submit_log(app: &mut App) -> anyhow::Result<()> {
+    let Some(selected) = app.get_selected_item() else {
+        return Ok(());
+    };
+    let id = selected.0.commitment.id;
+    let unit = selected.0.commitment.unit();
+
+    if let Some(end) = selected.0.commitment.end_monday
+        && end < get_monday_this_week()
+    {
+        app.set_message(format!("#{id} ended in the week of {end}"));
+        app.switch_input_mode(InputMode::Normal);
+        app.mark_dirty(true);
+        app.refresh_from_db_if_dirty().await?;
+        return Ok(());
+    }
+
+    let amount = match unit.parse_amount(&app.input_buffer) {
+        Ok(amount) => amount,
+        Err(e) => {
+            app.set_message(format!("Invalid amount: {e}."));
+            return Ok(());
+        }
+    };
+
+    app.set_message("Your request is being processed...");
+
+    let log_result = log_record_id(app.get_pool(), id, amount, None).await;
+
+    match log_result {
+        Ok(id) => {
+            app.set_message(format!("Logging to #{id} is successful."));
+            app.mark_dirty(true);
+            app.switch_input_mode(InputMode::Normal);
+        }
+        Err(e) => {
+            app.set_message(format!("Logging to #{id} is unsuccessful because {e}."));
+        }
+    };
+
+    app.refresh_from_db_if_dirty().await?;
+
+    Ok(())
+}
+
+async fn handle_week_logs_mode(key: event::KeyEvent, app: &mut App) -> Result<bool, anyhow::Error> {
+    if matches!(key.code, KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter) {
+        app.switch_input_mode(InputMode::Normal);
+    }
+
+    Ok(false)
+}
+
+async fn handle_search_mode(key: event::KeyEvent, app: &mut App) -> Result<bool, anyhow::Error> {
+    match key.code {
+        KeyCode::Esc => {
+            app.switch_input_mode(InputMode::Normal);
+            app.edit_search(String::clear);
+        }
+        KeyCode::Enter => {
+            app.switch_input_mode(InputMode::Normal);
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.edit_search(String::clear);
+        }
+        KeyCode::Char(c) => {
+            app.edit_search(|search| search.push(c));
+        }
+        KeyCode::Backspace => {
+            app.edit_search(|search| {
+                search.pop();
+            });
+        }
+        KeyCode::Down => {
+            app.next_match(1);
+        }
+        KeyCode::Up => {
+            app.previous_match(1);
         }
         _ => {}
     }
@@ -1,32 +1,112 @@
 use std::collections::HashMap;
 
-use chrono::{Duration, NaiveDate};
+use chrono::{Datelike, Local, NaiveDate};
 use ratatui::{
     Frame,
-    layout::{Constraint, Direction, Flex, Layout, Rect},
-    style::{Color, Modifier, Style, Stylize, palette::tailwind},
+    layout::{Constraint, Direction, Flex, Layout, Margin, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    symbols::Marker,
     text::{Line, Span},
     widgets::{
-        Bar, BarChart, BarGroup, Block, BorderType, Borders, Clear, Gauge, List, ListItem,
-        Paragraph, Wrap,
+        Axis, Bar, BarChart, BarGroup, Block, BorderType, Borders, Chart, Clear, Dataset, Gauge,
+        GraphType, LegendPosition, List, ListItem, Paragraph, Wrap,
     },
 };
 
 use crate::{
-    app::{App, CommitmentDisplayRecord, CreateCommitmentInputField, InputMode},
-    model::WeeklyStat,
-    util::get_monday_this_week,
+    app::{
+        App, Button, ClickTarget, CommitmentDisplayRecord, CreateCommitmentInputField, DetailView,
+        InputMode, ListFilter,
+    },
+    keymap::Action,
+    model::{CarryOver, Goal, GoalDirection, WeeklyStat},
+    stats::{DEFAULT_RECENT_WEEKS, compute_commitment_stats, forecast_week, milestone_progress},
+    theme::Theme,
+    unit::Unit,
+    util::{
+        carried_balance, cumulative_series, fuzzy_positions, get_monday_this_week, session_totals,
+        week_start, weekly_hours_since, weeks_since,
+    },
 };
 
+const CHART_BAR_GAP: u16 = 1;
+
 struct HistorySummary {
     pub start_monday: NaiveDate,
+    pub end_monday: Option<NaiveDate>,
     pub weeks_passed: i64,
+    pub weekly_target: f64,
     pub total_required: f64,
     pub total_done: f64,
     pub delta: f64,
+    /// Sessions done and required, when the commitment has a sessions target
+    pub sessions: Option<(i64, i64)>,
+    pub carry_over: CarryOver,
+    pub direction: GoalDirection,
+    pub unit: Unit,
+}
+
+impl HistorySummary {
+    fn period_text(&self) -> String {
+        let start = self.start_monday.format("%Y-%m-%d");
+        match self.end_monday {
+            Some(end) => format!("{start} (last week {})", end.format("%Y-%m-%d")),
+            None => start.to_string(),
+        }
+    }
+
+    fn status_text(&self) -> String {
+        let amount = self.unit.format(self.delta.abs());
+        let status = match self.direction {
+            GoalDirection::AtLeast if self.delta < -1e-6 => format!("Due by {amount}"),
+            GoalDirection::AtLeast if self.delta > 1e-6 => format!("Overdone by {amount}"),
+            GoalDirection::AtMost if self.delta > 1e-6 => format!("Over limit by {amount}"),
+            GoalDirection::AtMost if self.delta < -1e-6 => format!("Under limit by {amount}"),
+            _ => "On track".to_string(),
+        };
+
+        match self.carry_over {
+            CarryOver::Full => status,
+            CarryOver::None => format!("{status} (this week only)"),
+            CarryOver::Capped(cap) => {
+                format!("{status} (carry-over capped at {})", self.unit.format(cap))
+            }
+            CarryOver::Decay(pct) => format!("{status} (carry-over decays {pct}%/week)"),
+        }
+    }
+
+    fn sessions_text(&self) -> Option<String> {
+        self.sessions.map(|(done, required)| {
+            if done < required {
+                format!("{done}/{required} (behind by {})", required - done)
+            } else {
+                format!("{done}/{required}")
+            }
+        })
+    }
+
+    fn status_color(&self, theme: &Theme) -> Color {
+        let behind = match self.direction {
+            GoalDirection::AtLeast => -self.delta,
+            GoalDirection::AtMost => self.delta,
+        };
+        if behind > 0.0 {
+            theme.bad
+        } else if behind < 0.0 {
+            theme.good
+        } else {
+            theme.muted
+        }
+    }
 }
 
-fn draw_horizontal_separator(f: &mut Frame, area: Rect, spacer: Rect, sep_char: char) {
+fn draw_horizontal_separator(
+    f: &mut Frame,
+    area: Rect,
+    spacer: Rect,
+    sep_char: char,
+    theme: &Theme,
+) {
     let separator_area = Rect {
         x: area.x,
         y: spacer.y,
@@ -39,14 +119,14 @@ fn draw_horizontal_separator(f: &mut Frame, area: Rect, spacer: Rect, sep_char:
             .wrap(Wrap { trim: false })
             .style(
                 Style::default()
-                    .fg(tailwind::GRAY.c600)
+                    .fg(theme.muted)
                     .add_modifier(Modifier::BOLD),
             ),
         separator_area,
     );
 }
 
-fn draw_vertical_separator(f: &mut Frame, area: Rect, spacer: Rect, sep_char: char) {
+fn draw_vertical_separator(f: &mut Frame, area: Rect, spacer: Rect, sep_char: char, theme: &Theme) {
     let separator_area = Rect {
         x: spacer.x,
         y: area.y,
@@ -59,50 +139,48 @@ fn draw_vertical_separator(f: &mut Frame, area: Rect, spacer: Rect, sep_char: ch
             .wrap(Wrap { trim: false })
             .style(
                 Style::default()
-                    .fg(tailwind::GRAY.c600)
+                    .fg(theme.muted)
                     .add_modifier(Modifier::BOLD),
             ),
         separator_area,
     );
 }
 
-fn compute_history_summary(
-    start_monday: NaiveDate,
-    weekly_target_hours: f64,
-    stats: &[WeeklyStat],
-) -> HistorySummary {
-    let this_monday = get_monday_this_week();
-    let weeks_passed = ((this_monday - start_monday).num_weeks() + 1).max(0);
-
-    let total_done: f64 = stats.iter().map(|s| s.total_hours).sum();
-    let total_required = weekly_target_hours * weeks_passed as f64;
-    let delta = total_done - total_required;
+fn compute_history_summary(goal: &Goal, stats: &[WeeklyStat]) -> HistorySummary {
+    let weeks = weekly_hours_since(goal, stats);
+    let weeks_passed = weeks.len() as i64;
+    let total_done = weeks.iter().fold(0.0, |acc, (_, hours)| acc + hours);
+    let total_required = goal.weekly_target_hours * weeks_passed as f64;
+    let delta = carried_balance(goal, &weeks);
 
     HistorySummary {
-        start_monday,
+        start_monday: goal.start_monday,
+        end_monday: goal.end_monday,
         weeks_passed,
+        weekly_target: goal.weekly_target_hours,
         total_required,
         total_done,
         delta,
+        sessions: session_totals(goal, stats),
+        carry_over: goal.carry_over,
+        direction: goal.direction,
+        unit: goal.unit.clone(),
     }
 }
 
-fn render_commitment_history_summary_as_paragraph(summary: HistorySummary) -> Paragraph<'static> {
-    let status_text = if summary.delta < -1e-6 {
-        format!("Due by {:.1} h", -summary.delta + 0.0)
-    } else if summary.delta > 1e-6 {
-        format!("Overdone by {:.1} h", summary.delta + 0.0)
-    } else {
-        "On track".to_string()
-    };
-    let lines = vec![
+fn render_commitment_history_summary_as_paragraph(
+    summary: HistorySummary,
+    theme: &Theme,
+) -> Paragraph<'static> {
+    let status_text = summary.status_text();
+    let mut lines = vec![
         Line::from(""),
         Line::from(vec![
             Span::styled(
                 "Start Monday: ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw(summary.start_monday.format("%Y-%m-%d").to_string()),
+            Span::raw(summary.period_text()),
         ]),
         Line::from(vec![
             Span::styled(
@@ -117,44 +195,50 @@ fn render_commitment_history_summary_as_paragraph(summary: HistorySummary) -> Pa
                 "Weekly Target:  ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw(format!(
-                "{:.1} h",
-                summary.total_required / (summary.weeks_passed as f64) + 0.0
-            )),
+            Span::raw(summary.unit.format(summary.weekly_target)),
         ]),
         Line::from(""),
         Line::from(vec![
             Span::styled(
-                "Required:        ",
+                if summary.direction.is_limit() {
+                    "Allowed:         "
+                } else {
+                    "Required:        "
+                },
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw(format!("{:.1} h", summary.total_required)),
+            Span::raw(summary.unit.format(summary.total_required)),
         ]),
         Line::from(vec![
             Span::styled(
                 "Done:            ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw(format!("{:.1} h", summary.total_done + 0.0)),
+            Span::raw(summary.unit.format(summary.total_done)),
         ]),
+    ];
+    if let Some(sessions) = summary.sessions_text() {
+        lines.push(Line::from(vec![
+            Span::styled(
+                "Sessions:        ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(sessions),
+        ]));
+    }
+    lines.extend([
         Line::from(""),
         Line::from(vec![
             Span::styled(
                 "Accumulated Status: ",
                 Style::default()
                     .add_modifier(Modifier::BOLD)
-                    .fg(if summary.delta < 0.0 {
-                        Color::Red
-                    } else if summary.delta > 0.0 {
-                        Color::Green
-                    } else {
-                        Color::Gray
-                    }),
+                    .fg(summary.status_color(theme)),
             ),
             Span::raw(status_text),
         ]),
         Line::from(""),
-    ];
+    ]);
     Paragraph::new(lines).wrap(Wrap { trim: false }).block(
         Block::default()
             .borders(Borders::ALL)
@@ -172,11 +256,12 @@ fn popup_area(area: Rect, constraint_x: Constraint, constraint_y: Constraint) ->
 }
 
 pub fn draw(f: &mut Frame, app: &mut App) {
+    app.clear_click_targets();
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Min(1),
-            Constraint::Percentage(15),
+            Constraint::Percentage(30),
             Constraint::Length(1),
         ])
         .split(f.area());
@@ -197,13 +282,48 @@ pub fn draw(f: &mut Frame, app: &mut App) {
 
         // TODO: Implement the UI
         InputMode::CreateCommitment(_) => draw_create_commitment_overlay(f, app),
+        InputMode::ConfirmDelete(_) => draw_confirm_delete_overlay(f, app),
+        InputMode::PickPreset => draw_preset_overlay(f, app),
+        InputMode::Help => draw_help_overlay(f, app),
+        InputMode::WeekLogs(_) => draw_week_logs_overlay(f, app),
+        // The search is typed into the bottom of the commitments list
+        InputMode::Search => {}
+    }
+}
+
+/// Draw clickable `buttons` at the right end of the bottom border of an overlay at `area`
+fn draw_buttons(f: &mut Frame, app: &mut App, area: Rect, buttons: &[(&str, Button)]) {
+    let row = Rect {
+        x: area.x + 1,
+        y: area.bottom().saturating_sub(1),
+        width: area.width.saturating_sub(2),
+        height: 1,
+    };
+    let spans: Vec<Span> = buttons
+        .iter()
+        .map(|(label, _)| Span::styled(format!("[ {label} ]"), app.theme.selected()))
+        .collect();
+    let total: u16 = spans.iter().map(|span| span.width() as u16 + 1).sum();
+
+    let mut x = row.right().saturating_sub(total).max(row.x);
+    for (span, (_, button)) in spans.into_iter().zip(buttons) {
+        let width = (span.width() as u16).min(row.right().saturating_sub(x));
+        let button_area = Rect {
+            x,
+            y: row.y,
+            width,
+            height: 1,
+        };
+        f.render_widget(span, button_area);
+        app.add_click_target(button_area, ClickTarget::Button(*button));
+        x += width + 1;
     }
 }
 
 fn draw_log_overlay(f: &mut Frame, app: &mut App) {
     let block = Block::bordered()
         .border_type(BorderType::Rounded)
-        .title_bottom("Log Hours");
+        .title_bottom("Log Progress");
     let area = popup_area(f.area(), Constraint::Percentage(40), Constraint::Length(22));
     let inner = block.inner(area);
 
@@ -227,22 +347,25 @@ fn draw_log_overlay(f: &mut Frame, app: &mut App) {
     // Draw the title
     f.render_widget(
         Span::styled(
-            format!("Logging for \"{}\" (#{})", item.0.name, item.0.id),
+            format!(
+                "Logging for \"{}\" (#{})",
+                item.0.commitment.name, item.0.commitment.id
+            ),
             Style::default()
                 .bold()
                 .underlined()
-                .bg(tailwind::STONE.c900)
-                .fg(tailwind::ROSE.c500),
+                .bg(app.theme.title_bg)
+                .fg(app.theme.title),
         ),
         chunks[0],
     );
 
     // Draw a separator
-    draw_horizontal_separator(f, inner, spacers[1], '-');
+    draw_horizontal_separator(f, inner, spacers[1], '-', &app.theme);
 
     // Display the details of the commitment
-    let summary = compute_history_summary(item.0.start_monday, item.0.weekly_target_hours, &item.1);
-    let details_widget = render_commitment_history_summary_as_paragraph(summary);
+    let summary = compute_history_summary(&item.0.commitment.goal(), &item.1);
+    let details_widget = render_commitment_history_summary_as_paragraph(summary, &app.theme);
     f.render_widget(details_widget, chunks[1]);
 
     f.render_widget(
@@ -261,6 +384,223 @@ fn draw_log_overlay(f: &mut Frame, app: &mut App) {
         ),
         chunks[2],
     );
+
+    draw_buttons(
+        f,
+        app,
+        area,
+        &[("enter: Log", Button::Log), ("esc: Cancel", Button::Cancel)],
+    );
+}
+
+fn draw_confirm_delete_overlay(f: &mut Frame, app: &mut App) {
+    let InputMode::ConfirmDelete(preview) = &app.input_mode else {
+        return;
+    };
+    let block = Block::bordered()
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(app.theme.bad))
+        .title_bottom("Delete Commitment");
+    let area = popup_area(f.area(), Constraint::Percentage(40), Constraint::Length(7));
+    let inner = block.inner(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("Delete \"{}\" (#{})?", preview.name, preview.id),
+            Style::default().bold().fg(app.theme.title),
+        )),
+        Line::from(format!("{} log(s) will be removed for good.", preview.logs)),
+    ];
+    if preview.sub_commitments > 0 {
+        lines.push(Line::from(format!(
+            "{} sub-commitment(s) will become top-level.",
+            preview.sub_commitments
+        )));
+    }
+
+    f.render_widget(
+        Paragraph::new(lines).wrap(Wrap { trim: true }),
+        inner.inner(Margin::new(1, 0)),
+    );
+    draw_buttons(
+        f,
+        app,
+        area,
+        &[("y: Delete", Button::Delete), ("n: Cancel", Button::Cancel)],
+    );
+}
+
+fn draw_preset_overlay(f: &mut Frame, app: &mut App) {
+    let block = Block::bordered()
+        .border_type(BorderType::Rounded)
+        .title_bottom("Log Preset");
+    let height = app.get_presets().len() as u16 + 2;
+    let area = popup_area(
+        f.area(),
+        Constraint::Percentage(40),
+        Constraint::Length(height),
+    );
+
+    let items: Vec<ListItem> = app
+        .get_presets()
+        .iter()
+        .enumerate()
+        .map(|(idx, (name, preset))| {
+            let key = if idx < 9 {
+                format!("{} ", idx + 1)
+            } else {
+                "  ".to_string()
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(key, Style::default().bold()),
+                Span::styled(format!("@{name}"), Style::default().fg(app.theme.accent)),
+                Span::raw(format!(": {} to '{}'", preset.amount, preset.commitment)),
+                Span::styled(
+                    preset
+                        .note
+                        .as_ref()
+                        .map(|note| format!(" ({note})"))
+                        .unwrap_or_default(),
+                    Style::default().fg(app.theme.muted),
+                ),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(app.theme.selected())
+        .highlight_symbol("> ");
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut app.preset_state);
+
+    let rows = area.inner(Margin::new(1, 1));
+    let offset = app.preset_state.offset();
+    for (row, idx) in (offset..app.get_presets().len())
+        .enumerate()
+        .take(rows.height as usize)
+    {
+        let row_area = Rect {
+            y: rows.y + row as u16,
+            height: 1,
+            ..rows
+        };
+        app.add_click_target(row_area, ClickTarget::Preset(idx));
+    }
+    draw_buttons(f, app, area, &[("esc: Cancel", Button::Cancel)]);
+}
+
+/// Every action with the keys bound to it, generated from the keymap in use
+fn draw_help_overlay(f: &mut Frame, app: &mut App) {
+    let block = Block::bordered()
+        .border_type(BorderType::Rounded)
+        .title_bottom("Key Bindings");
+    let area = popup_area(
+        f.area(),
+        Constraint::Percentage(60),
+        Constraint::Length(Action::ALL.len() as u16 + 2),
+    );
+    let inner = block.inner(area);
+
+    let rows: Vec<(String, Action)> = Action::ALL
+        .iter()
+        .map(|action| (app.keymap.keys_for(*action), *action))
+        .collect();
+    let key_width = rows
+        .iter()
+        .map(|(keys, _)| keys.chars().count())
+        .max()
+        .unwrap_or(0);
+    let lines: Vec<Line> = rows
+        .into_iter()
+        .map(|(keys, action)| {
+            let keys = if keys.is_empty() {
+                "-".to_string()
+            } else {
+                keys
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{keys:<key_width$}  "),
+                    Style::default().bold().fg(app.theme.accent),
+                ),
+                Span::raw(action.description()),
+                Span::styled(
+                    format!(" ({})", action.name()),
+                    Style::default().fg(app.theme.muted),
+                ),
+            ])
+        })
+        .collect();
+    app.help_scroll = app
+        .help_scroll
+        .min((lines.len() as u16).saturating_sub(inner.height));
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+    f.render_widget(
+        Paragraph::new(lines).scroll((app.help_scroll, 0)),
+        inner.inner(Margin::new(1, 0)),
+    );
+    draw_buttons(f, app, area, &[("esc: Close", Button::Close)]);
+}
+
+/// The logs behind a bar of the weekly chart
+fn draw_week_logs_overlay(f: &mut Frame, app: &mut App) {
+    let InputMode::WeekLogs(week_logs) = &app.input_mode else {
+        return;
+    };
+    let theme = app.theme;
+    let unit = &week_logs.unit;
+
+    let total: f64 = week_logs.logs.iter().map(|log| log.hours).sum();
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("\"{}\": {}", week_logs.name, unit.format(total)),
+            Style::default().bold().fg(theme.title),
+        )),
+        Line::from(""),
+    ];
+    if week_logs.logs.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "Nothing was logged this week",
+            Style::default().fg(theme.muted),
+        )));
+    }
+    lines.extend(week_logs.logs.iter().map(|log| {
+        Line::from(vec![
+            Span::styled(
+                format!("{}  ", log.logged_at.format("%a %m-%d")),
+                Style::default().bold(),
+            ),
+            Span::raw(unit.format(log.hours)),
+            Span::styled(
+                log.note
+                    .as_ref()
+                    .map(|note| format!("  {note}"))
+                    .unwrap_or_default(),
+                Style::default().fg(theme.muted),
+            ),
+        ])
+    }));
+
+    let block = Block::bordered()
+        .border_type(BorderType::Rounded)
+        .title_bottom(format!("Week of {}", week_logs.week.format("%Y-%m-%d")));
+    let area = popup_area(
+        f.area(),
+        Constraint::Percentage(50),
+        Constraint::Length(lines.len() as u16 + 2),
+    );
+    let inner = block.inner(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+    f.render_widget(Paragraph::new(lines), inner.inner(Margin::new(1, 0)));
+    draw_buttons(f, app, area, &[("esc: Close", Button::Close)]);
 }
 
 fn draw_create_commitment_overlay(f: &mut Frame, app: &mut App) {
@@ -300,19 +640,10 @@ fn draw_create_commitment_overlay(f: &mut Frame, app: &mut App) {
     );
 
     f.render_widget(
-        Paragraph::new(Line::from(vec![
-            Span::styled(
-                app.input_buffer.as_str(),
-                Style::default().bold().underlined(),
-            ),
-            if let InputMode::CreateCommitment(CreateCommitmentInputField::TargetHour) =
-                app.input_mode
-            {
-                Span::styled(" ◁", Style::default().bold())
-            } else {
-                Span::raw("")
-            },
-        ]))
+        Paragraph::new(Line::from(vec![Span::styled(
+            app.input_buffer.as_str(),
+            Style::default().bold().underlined(),
+        )]))
         .block(
             Block::default()
                 .borders(Borders::ALL)
@@ -330,12 +661,15 @@ fn draw_progress_pane(f: &mut Frame, app: &App, area: Rect) {
     let inner = block.inner(area);
     f.render_widget(block, area);
 
-    let active: Vec<&CommitmentDisplayRecord> =
-        app.get_items().iter().filter(|c| c.0.active).collect();
+    let active: Vec<&CommitmentDisplayRecord> = app
+        .get_items()
+        .iter()
+        .filter(|c| c.0.commitment.active)
+        .collect();
 
     if active.is_empty() {
         let p = Paragraph::new("No active commitments.")
-            .style(Style::default().fg(Color::DarkGray))
+            .style(Style::default().fg(app.theme.muted))
             .wrap(Wrap { trim: true });
         f.render_widget(p, inner);
         return;
@@ -351,35 +685,77 @@ fn draw_progress_pane(f: &mut Frame, app: &App, area: Rect) {
         )
         .split(inner);
 
-    for (row_area, c) in rows.into_iter().zip(active.into_iter()) {
-        let ratio = if c.0.weekly_target_hours <= 0.0 {
+    for (row_area, c) in rows.iter().zip(active) {
+        let ratio = if c.0.commitment.weekly_target_hours <= 0.0 {
             0.0
         } else {
-            (c.0.current_week_total.unwrap_or(0.0) / c.0.weekly_target_hours).min(1.5) // To have at least something       
+            (c.0.current_week_total.unwrap_or(0.0) / c.0.commitment.weekly_target_hours).min(1.5) // To have at least something       
         };
 
-        let mut gauge_style = Style::default().fg(tailwind::GREEN.c700);
-        if ratio >= 1.0 {
-            gauge_style = Style::default()
-                .fg(tailwind::CYAN.c600)
-                .add_modifier(Modifier::BOLD);
-        }
+        let is_limit = c.0.commitment.goal_direction.is_limit();
+        let gauge_style = if is_limit && ratio > 1.0 {
+            Style::default()
+                .fg(app.theme.bad)
+                .add_modifier(Modifier::BOLD)
+        } else if is_limit && ratio >= app.progress.high / 100.0 {
+            Style::default().fg(app.theme.warn)
+        } else if !is_limit && ratio >= 1.0 {
+            Style::default()
+                .fg(app.theme.reached)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(app.theme.progress)
+        };
 
+        let unit = c.0.commitment.unit();
+        let sessions =
+            c.0.commitment
+                .weekly_target_sessions
+                .map(|n| format!(", {}/{n} sessions", c.0.current_week_sessions))
+                .unwrap_or_default();
         let label = format!(
-            "{} ({:.1}/{:.1}h)",
-            c.0.name,
-            c.0.current_week_total.unwrap_or(0.0),
-            c.0.weekly_target_hours
+            "{}{} ({}/{}{sessions})",
+            if is_limit { "≤ " } else { "" },
+            c.0.commitment.name,
+            unit.format_value(c.0.current_week_total.unwrap_or(0.0)),
+            unit.format(c.0.commitment.weekly_target_hours)
         );
         let gauge = Gauge::default()
             .block(Block::default().borders(Borders::TOP | Borders::BOTTOM))
             .gauge_style(gauge_style)
-            .ratio((ratio / 1.5).min(1.0).max(0.005))
+            .ratio((ratio / 1.5).clamp(0.005, 1.0))
             .label(Span::raw(format!("{} %", (ratio * 100.0).round())))
             .use_unicode(true);
 
         f.render_widget(gauge, *row_area);
 
+        // Where the gauge should be by the end of today if the week plan is followed
+        if c.0.commitment.weekly_target_hours > 0.0 {
+            let expected = c.2.expected_by(Local::now().weekday(), week_start())
+                / c.0.commitment.weekly_target_hours;
+            let offset =
+                ((expected / 1.5).clamp(0.0, 1.0) * row_area.width.saturating_sub(1) as f64) as u16;
+            let behind = if is_limit {
+                ratio > expected
+            } else {
+                ratio < expected
+            };
+            let marker_style = if behind {
+                Style::default().fg(app.theme.bad)
+            } else {
+                Style::default().fg(app.theme.muted)
+            };
+            f.render_widget(
+                Paragraph::new("▼").style(marker_style),
+                Rect {
+                    x: row_area.x + offset,
+                    y: row_area.y,
+                    width: 1,
+                    height: 1,
+                },
+            );
+        }
+
         let lable_width = label.chars().count() as u16;
         let label_area = Rect {
             x: row_area.x + 2,
@@ -390,47 +766,154 @@ fn draw_progress_pane(f: &mut Frame, app: &App, area: Rect) {
         let label_widget = Paragraph::new(label).style(Style::default().bg(Color::Reset));
 
         f.render_widget(label_widget, label_area);
+
+        let forecast = forecast_week(
+            c.0.current_week_total.unwrap_or(0.0),
+            c.0.commitment.weekly_target_hours,
+            Local::now().date_naive(),
+        );
+        let forecast_text = format!(
+            " {} {}/day | projected {} ",
+            if is_limit { "allowed" } else { "need" },
+            unit.format(forecast.required_daily),
+            unit.format(forecast.projected_total)
+        );
+        let forecast_width = (forecast_text.chars().count() as u16).min(row_area.width);
+        let forecast_area = Rect {
+            x: row_area.x + row_area.width.saturating_sub(forecast_width),
+            y: row_area.y + row_area.height.saturating_sub(1),
+            width: forecast_width,
+            height: 1,
+        };
+        let forecast_style = if c
+            .0
+            .commitment
+            .goal_direction
+            .meets(forecast.projected_total, c.0.commitment.weekly_target_hours)
+        {
+            Style::default().fg(app.theme.good)
+        } else {
+            Style::default().fg(app.theme.warn)
+        };
+
+        f.render_widget(
+            Paragraph::new(forecast_text).style(forecast_style),
+            forecast_area,
+        );
     }
 }
 
 fn draw_commitments_list_pane(f: &mut Frame, app: &mut App, area: Rect) {
-    let block = Block::default()
-        .title("Commitments (j/k, gg/G, a, l, r)")
-        .borders(Borders::ALL);
+    let keymap = &app.keymap;
+    let title = format!(
+        "Commitments ({}/{}, {}/{}, {}, {}: help)",
+        keymap.key_for(Action::Down),
+        keymap.key_for(Action::Up),
+        keymap.key_for(Action::First),
+        keymap.key_for(Action::Last),
+        keymap.key_for(Action::Fold),
+        keymap.key_for(Action::Help),
+    );
+    let mut block = Block::default().title(title).borders(Borders::ALL);
+    if app.filter != ListFilter::All {
+        block = block.title_bottom(
+            Line::styled(
+                format!("[{}]", app.filter),
+                Style::default().bold().fg(app.theme.accent),
+            )
+            .right_aligned(),
+        );
+    }
+    let searching = matches!(app.input_mode, InputMode::Search);
+    if searching || !app.search.is_empty() {
+        block = block.title_bottom(Line::from(vec![
+            Span::styled(
+                format!("/{}", app.search),
+                Style::default().bold().fg(app.theme.accent),
+            ),
+            Span::raw(if searching { "▏" } else { "" }),
+            Span::styled(
+                format!(" {} match(es)", app.match_count()),
+                Style::default().fg(app.theme.muted),
+            ),
+        ]));
+    }
     let inner = block.inner(area);
     f.render_widget(block, area);
 
     let items: Vec<ListItem> = app
-        .get_items()
-        .iter()
-        .map(|c| {
-            let marker = if c.0.active { "[A]" } else { "[ ]" };
-            let line = format!(
-                "{} #{:<3} {} (target {:.1}h)",
-                marker, c.0.id, c.0.name, c.0.weekly_target_hours
+        .get_visible_items()
+        .into_iter()
+        .map(|(c, depth, has_children, folded)| {
+            let marker = if c.0.commitment.active { "[A]" } else { "[ ]" };
+            let fold = match (has_children, folded) {
+                (true, true) => "▸ ",
+                (true, false) => "▾ ",
+                (false, _) => "",
+            };
+            let prefix = format!(
+                "{} #{:<3} {}{}",
+                marker,
+                c.0.commitment.id,
+                "  ".repeat(depth),
+                fold
             );
-            let style = if c.0.active {
+            let suffix = format!(
+                " ({} {})",
+                if c.0.commitment.goal_direction.is_limit() {
+                    "limit"
+                } else {
+                    "target"
+                },
+                c.0.commitment
+                    .unit()
+                    .format(c.0.commitment.weekly_target_hours)
+            );
+            // Parents only shown for the matches below them are dimmed like archived ones
+            let style = if c.0.commitment.active && (!app.is_filtering() || app.is_match(c)) {
                 Style::default()
             } else {
-                Style::default().fg(Color::DarkGray)
+                Style::default().fg(app.theme.muted)
             };
-            ListItem::new(Span::styled(line, style))
+
+            let mut spans = vec![Span::styled(prefix, style)];
+            match fuzzy_positions(&app.search, &c.0.commitment.name).filter(|p| !p.is_empty()) {
+                Some(positions) => {
+                    spans.extend(c.0.commitment.name.chars().enumerate().map(|(idx, ch)| {
+                        if positions.contains(&idx) {
+                            Span::styled(ch.to_string(), style.bold().fg(app.theme.accent))
+                        } else {
+                            Span::styled(ch.to_string(), style)
+                        }
+                    }))
+                }
+                None => spans.push(Span::styled(c.0.commitment.name.clone(), style)),
+            }
+            spans.push(Span::styled(suffix, style));
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
+    let row_count = items.len();
     let list = List::new(items)
-        .highlight_style(
-            Style::default()
-                .bg(tailwind::BLUE.c500)
-                .fg(Color::White)
-                .add_modifier(Modifier::BOLD),
-        )
+        .highlight_style(app.theme.selected().add_modifier(Modifier::BOLD))
         .highlight_symbol(">> ");
 
     f.render_stateful_widget(list, inner, &mut app.list_state);
+
+    app.add_click_target(inner, ClickTarget::List);
+    let offset = app.list_state.offset();
+    for (row, idx) in (offset..row_count).enumerate().take(inner.height as usize) {
+        let row_area = Rect {
+            y: inner.y + row as u16,
+            height: 1,
+            ..inner
+        };
+        app.add_click_target(row_area, ClickTarget::Row(idx));
+    }
 }
 
-fn draw_detail_pane(f: &mut Frame, app: &App, area: Rect) {
+fn draw_detail_pane(f: &mut Frame, app: &mut App, area: Rect) {
     let block = Block::default().title("Detail").borders(Borders::ALL);
     let inner = block.inner(area);
     f.render_widget(block, area);
@@ -441,64 +924,259 @@ fn draw_detail_pane(f: &mut Frame, app: &App, area: Rect) {
         .spacing(2)
         .split_with_spacers(inner);
 
+    if app.detail_view == DetailView::Weekly {
+        fit_weekly_chart(app, chunks[0].width);
+    }
+
     let Some(selected) = app.get_selected_item() else {
         let p =
-            Paragraph::new("No commitment selected").style(Style::default().fg(Color::DarkGray));
+            Paragraph::new("No commitment selected").style(Style::default().fg(app.theme.muted));
         f.render_widget(p, inner);
         return;
     };
 
+    let click_targets = match app.detail_view {
+        DetailView::Weekly => draw_weekly_chart(f, app, selected, chunks[0]),
+        DetailView::Cumulative => {
+            draw_cumulative_chart(f, selected, &app.theme, chunks[0]);
+            Vec::new()
+        }
+    };
+
+    // Draw the summary
+    draw_history_summary(f, app, chunks[1]);
+
+    // Draw the separator
+    draw_vertical_separator(f, inner, spacers[1], '|', &app.theme);
+
+    for (area, target) in click_targets {
+        app.add_click_target(area, target);
+    }
+}
+
+/// Short label for a week bar, shrinking the date format when bars are too narrow.
+fn week_label(week: NaiveDate, bar_width: u16) -> String {
+    if bar_width >= 5 {
+        week.format("%m-%d").to_string()
+    } else {
+        week.format("%d").to_string()
+    }
+}
+
+/// Remember how many weeks fit in `width` and keep the scroll offset within the weeks there are,
+/// so that scrolling back towards the current week responds right away
+fn fit_weekly_chart(app: &mut App, width: u16) {
+    let capacity =
+        ((width + CHART_BAR_GAP) / (app.chart_bar_width + CHART_BAR_GAP)).max(1) as usize;
+    let weeks = app
+        .get_selected_item()
+        .map_or(0, |sel| weeks_since(&sel.0.commitment.goal()).len());
+    app.chart_capacity = capacity;
+    app.chart_offset = app.chart_offset.min(weeks.saturating_sub(capacity));
+}
+
+/// Draw the weekly chart, returning the area of the chart and of every bar for the mouse
+fn draw_weekly_chart(
+    f: &mut Frame,
+    app: &App,
+    selected: &CommitmentDisplayRecord,
+    area: Rect,
+) -> Vec<(Rect, ClickTarget)> {
     let weekly_stats = &selected.1;
+    let target = selected.0.commitment.weekly_target_hours;
+    let direction = selected.0.commitment.goal_direction;
+    let unit = selected.0.commitment.unit();
 
     // Prepare for checking historic hours
-    let start_monday: NaiveDate = selected.0.start_monday;
     let end_monday: NaiveDate = get_monday_this_week();
-
-    let mut weeks: Vec<NaiveDate> = Vec::new();
-    let mut current = start_monday;
-    while current <= end_monday {
-        weeks.push(current);
-        current += Duration::weeks(1);
-    }
+    let weeks = weeks_since(&selected.0.commitment.goal());
 
     let hours_by_week: HashMap<NaiveDate, f64> = weekly_stats
         .iter()
         .map(|s| (s.week_start, s.total_hours))
         .collect();
 
-    // Prepare bar chart entries
-    let bars: Vec<Bar> = weeks
+    // Only show the window of weeks that fits, ending `chart_offset` weeks before the latest one.
+    let bar_width = app.chart_bar_width;
+    let capacity = app.chart_capacity;
+    let offset = app.chart_offset;
+    let window_end = weeks.len() - offset;
+    let window_start = window_end.saturating_sub(capacity);
+    let window = &weeks[window_start..window_end];
+
+    // Prepare bar chart entries, scaled by 10 so that fractional hours still show up
+    let bars: Vec<Bar> = window
         .iter()
-        .enumerate()
-        .map(|(i, week)| {
+        .map(|week| {
             let hours = hours_by_week.get(week).copied().unwrap_or(0.0);
+            let is_current = *week == end_monday;
+            // A past week without a single log is treated as an off-week
+            let is_off = hours <= 0.0 && !is_current;
+
+            let bar_style = if target > 0.0 && direction.meets(hours, target) {
+                Style::default().fg(app.theme.good)
+            } else if is_current {
+                Style::default().fg(app.theme.warn)
+            } else {
+                Style::default().fg(app.theme.bad)
+            };
 
-            let bar_style = Style::default().fg(tailwind::ROSE.c500);
+            let label_style = if is_current {
+                Style::default()
+                    .fg(app.theme.accent)
+                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+            } else if is_off {
+                Style::default().fg(app.theme.muted)
+            } else {
+                Style::default()
+            };
 
             Bar::default()
-                .value(hours.round() as u64)
+                .value((hours * 10.0).round() as u64)
                 .style(bar_style)
-                .label(Line::from(format!("W{}", i + 1)))
-                .text_value(format!("{}h", hours.round()))
+                .label(Line::styled(week_label(*week, bar_width), label_style))
+                .text_value(if is_off {
+                    "off".to_string()
+                } else {
+                    unit.format_value(hours)
+                })
         })
         .collect();
 
-    let max = (selected.0.weekly_target_hours * 1.5).ceil() as u64;
+    let window_max = window
+        .iter()
+        .filter_map(|week| hours_by_week.get(week))
+        .fold(0.0_f64, |acc, h| acc.max(*h));
+    let max = (target * 1.5).max(window_max) * 10.0;
+
+    let mut title = format!(
+        "Weekly {} ({} {})",
+        unit,
+        if direction.is_limit() {
+            "limit"
+        } else {
+            "target"
+        },
+        unit.format(target)
+    );
+    if let (Some(first), Some(last)) = (window.first(), window.last()) {
+        title.push_str(&format!(
+            " {} ~ {}",
+            first.format("%Y-%m-%d"),
+            last.format("%Y-%m-%d")
+        ));
+    }
+    if window_start > 0 {
+        title.push_str(&format!(" [◀ {window_start} older]"));
+    }
+    if offset > 0 {
+        title.push_str(&format!(" [{offset} newer ▶]"));
+    }
 
+    let block = Block::default().title(title);
+    let bars_area = block.inner(area);
     let chart = BarChart::default()
-        .block(Block::default().title("Weekly Hours"))
+        .block(block)
         .data(BarGroup::default().bars(&bars))
-        .bar_width(4)
-        .bar_gap(1)
-        .max(max);
+        .bar_width(bar_width)
+        .bar_gap(CHART_BAR_GAP)
+        .max(max.ceil() as u64);
 
-    f.render_widget(chart, chunks[0]);
+    f.render_widget(chart, area);
 
-    // Draw the summary
-    draw_history_summary(f, app, chunks[1]);
+    let mut click_targets = vec![(area, ClickTarget::Chart)];
+    for (idx, week) in window.iter().enumerate() {
+        let x = bars_area.x + idx as u16 * (bar_width + CHART_BAR_GAP);
+        if x >= bars_area.right() {
+            break;
+        }
+        let bar_area = Rect {
+            x,
+            width: bar_width.min(bars_area.right() - x),
+            ..bars_area
+        };
+        click_targets.push((bar_area, ClickTarget::Week(*week)));
+    }
+    click_targets
+}
 
-    // Draw the separator
-    draw_vertical_separator(f, inner, spacers[1], '|');
+fn draw_cumulative_chart(
+    f: &mut Frame,
+    selected: &CommitmentDisplayRecord,
+    theme: &Theme,
+    area: Rect,
+) {
+    let series = cumulative_series(&selected.0.commitment.goal(), &selected.1);
+    let unit = selected.0.commitment.unit();
+
+    let (Some(first), Some(last)) = (series.first(), series.last()) else {
+        let p = Paragraph::new("Commitment has not started yet")
+            .style(Style::default().fg(theme.muted));
+        f.render_widget(p, area);
+        return;
+    };
+
+    let done: Vec<(f64, f64)> = series
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (i as f64, p.done))
+        .collect();
+    let required: Vec<(f64, f64)> = series
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (i as f64, p.required))
+        .collect();
+
+    let x_max = (series.len() as f64 - 1.0).max(1.0);
+    let y_max = last.done.max(last.required).max(1.0).ceil();
+
+    let datasets = vec![
+        Dataset::default()
+            .name("required")
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(theme.muted))
+            .data(&required),
+        Dataset::default()
+            .name("done")
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(theme.title))
+            .data(&done),
+    ];
+
+    let chart = Chart::new(datasets)
+        .block(Block::default().title(format!(
+            "Cumulative {} (balance {}{})",
+            unit,
+            if last.done >= last.required { "+" } else { "-" },
+            unit.format((last.done - last.required).abs())
+        )))
+        .x_axis(
+            Axis::default()
+                .bounds([0.0, x_max])
+                .labels([
+                    first.week_start.format("%Y-%m-%d").to_string(),
+                    last.week_start.format("%Y-%m-%d").to_string(),
+                ])
+                .style(Style::default().fg(theme.muted)),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, y_max])
+                .labels(["0".to_string(), format!("{:.0}", y_max)])
+                .style(Style::default().fg(theme.muted)),
+        )
+        .legend_position(Some(LegendPosition::TopLeft));
+
+    f.render_widget(chart, area);
+}
+
+fn format_week_amount(week: Option<(NaiveDate, f64)>, unit: &Unit) -> String {
+    match week {
+        Some((week, amount)) => format!("{} ({})", unit.format(amount), week.format("%Y-%m-%d")),
+        None => "-".to_string(),
+    }
 }
 
 fn draw_history_summary(f: &mut Frame, app: &App, area: Rect) {
@@ -513,27 +1191,19 @@ fn draw_history_summary(f: &mut Frame, app: &App, area: Rect) {
     let commitment: &CommitmentDisplayRecord = selected;
 
     // get the stats for this commitment (you may already have them cached in App)
-    let summary = compute_history_summary(
-        commitment.0.start_monday,
-        commitment.0.weekly_target_hours,
-        &commitment.1,
-    );
+    let goal = commitment.0.commitment.goal();
+    let summary = compute_history_summary(&goal, &commitment.1);
+    let stats = compute_commitment_stats(&goal, &commitment.1, DEFAULT_RECENT_WEEKS);
 
-    let status_text = if summary.delta < -1e-6 {
-        format!("Due by {:.1} h", -summary.delta + 0.0)
-    } else if summary.delta > 1e-6 {
-        format!("Overdone by {:.1} h", summary.delta + 0.0)
-    } else {
-        "On track".to_string()
-    };
+    let status_text = summary.status_text();
 
-    let lines = vec![
+    let mut lines = vec![
         Line::from(vec![
             Span::styled(
                 "Start Monday: ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw(summary.start_monday.format("%Y-%m-%d").to_string()),
+            Span::raw(summary.period_text()),
         ]),
         Line::from(vec![
             Span::styled(
@@ -544,30 +1214,114 @@ fn draw_history_summary(f: &mut Frame, app: &App, area: Rect) {
         ]),
         Line::from(""),
         Line::from(vec![
-            Span::styled("Required: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(format!("{:.1} h", summary.total_required)),
+            Span::styled(
+                if summary.direction.is_limit() {
+                    "Allowed:  "
+                } else {
+                    "Required: "
+                },
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(summary.unit.format(summary.total_required)),
         ]),
         Line::from(vec![
             Span::styled("Done:     ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(format!("{:.1} h", summary.total_done + 0.0)),
+            Span::raw(summary.unit.format(summary.total_done)),
         ]),
+    ];
+    if let Some(sessions) = summary.sessions_text() {
+        lines.push(Line::from(vec![
+            Span::styled("Sessions: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(sessions),
+        ]));
+    }
+    if let Some(progress) = milestone_progress(&goal, &commitment.1, Local::now().date_naive()) {
+        let countdown = match progress.days_left {
+            d if d < 0 => format!("{} days overdue", -d),
+            0 => "due today".to_string(),
+            d => format!("{d} days left"),
+        };
+        lines.push(Line::from(vec![
+            Span::styled("Goal:     ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(format!(
+                "{}/{} by {} ({countdown})",
+                goal.unit.format_value(progress.done),
+                goal.unit.format(progress.milestone.total),
+                progress.milestone.due.format("%Y-%m-%d")
+            )),
+        ]));
+
+        let (pace, color) = match progress.projected_completion {
+            _ if progress.is_complete() => ("reached".to_string(), app.theme.good),
+            Some(date) => (
+                format!(
+                    "{}/week needed, projected {}",
+                    goal.unit.format(progress.required_weekly),
+                    date.format("%Y-%m-%d")
+                ),
+                if progress.on_track() {
+                    app.theme.good
+                } else {
+                    app.theme.bad
+                },
+            ),
+            None => (
+                format!(
+                    "{}/week needed, no progress yet",
+                    goal.unit.format(progress.required_weekly)
+                ),
+                app.theme.bad,
+            ),
+        };
+        lines.push(Line::from(vec![
+            Span::styled("Pace:     ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(pace, Style::default().fg(color)),
+        ]));
+    }
+    lines.extend([
         Line::from(""),
         Line::from(vec![
             Span::styled(
                 "Accumulated Status: ",
                 Style::default()
                     .add_modifier(Modifier::BOLD)
-                    .fg(if summary.delta < 0.0 {
-                        Color::Red
-                    } else if summary.delta > 0.0 {
-                        Color::Green
-                    } else {
-                        Color::Gray
-                    }),
+                    .fg(summary.status_color(&app.theme)),
             ),
             Span::raw(status_text),
         ]),
-    ];
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Streak:   ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(format!(
+                "{} weeks (longest {})",
+                stats.current_streak, stats.longest_streak
+            )),
+        ]),
+        Line::from(vec![
+            Span::styled("Hit rate: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(format!(
+                "{:.0}% of last {} weeks",
+                stats.hit_rate(),
+                stats.recent_weeks
+            )),
+        ]),
+        Line::from(vec![
+            Span::styled("Avg/Med:  ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(format!(
+                "{} / {}",
+                goal.unit.format(stats.average_hours),
+                goal.unit.format(stats.median_hours)
+            )),
+        ]),
+        Line::from(vec![
+            Span::styled("Best:     ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(format_week_amount(stats.best_week, &goal.unit)),
+        ]),
+        Line::from(vec![
+            Span::styled("Worst:    ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(format_week_amount(stats.worst_week, &goal.unit)),
+        ]),
+    ]);
 
     let widget = Paragraph::new(lines).wrap(Wrap { trim: true });
 
@@ -575,44 +1329,90 @@ fn draw_history_summary(f: &mut Frame, app: &App, area: Rect) {
 }
 
 impl InputMode {
-    fn get_style(&self) -> Style {
+    fn get_style(&self, theme: &Theme) -> Style {
         match self {
-            InputMode::Normal => Style::default().bg(tailwind::PURPLE.c800),
-            InputMode::LogHours => Style::default().bg(tailwind::CYAN.c800),
-            InputMode::CreateCommitment(_) => Style::default().bg(tailwind::EMERALD.c800),
+            InputMode::Normal => theme.bg(theme.mode_normal),
+            InputMode::LogHours => theme.bg(theme.mode_log),
+            InputMode::CreateCommitment(_) => theme.bg(theme.mode_create),
+            InputMode::ConfirmDelete(_) => theme.bg(theme.mode_delete),
+            InputMode::PickPreset => theme.bg(theme.mode_log),
+            InputMode::Help | InputMode::WeekLogs(_) => theme.bg(theme.mode_normal),
+            InputMode::Search => theme.bg(theme.mode_log),
         }
     }
 }
 
-fn draw_footer(f: &mut Frame, app: &App, area: Rect) {
+fn draw_footer(f: &mut Frame, app: &mut App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Max(12),
+            Constraint::Max(16),
             Constraint::Fill(2),
             Constraint::Fill(3),
         ])
         .split(area);
 
-    f.render_widget(
+    // Keys typed so far of a longer binding, such as the `5g` of `5gg`
+    let pending = if app.pending_keys.is_empty() {
+        Span::raw("")
+    } else {
         Span::styled(
-            format!(" {} ", app.input_mode.to_string()),
-            app.input_mode.get_style(),
-        ),
+            format!(" {}", app.pending_keys),
+            Style::default().bold().fg(app.theme.accent),
+        )
+    };
+    f.render_widget(
+        Line::from(vec![
+            Span::styled(
+                format!(" {} ", app.input_mode),
+                app.input_mode.get_style(&app.theme),
+            ),
+            pending,
+        ]),
         chunks[0],
     );
 
     f.render_widget(
         Paragraph::new(app.get_quick_msg())
             .wrap(Wrap { trim: true })
-            .style(Style::default().fg(tailwind::RED.c300)),
+            .style(Style::default().fg(app.theme.message)),
         chunks[1],
     );
 
+    // The hints of normal mode can be clicked instead of pressing their keys
+    if matches!(app.input_mode, InputMode::Normal) {
+        let hints_area = chunks[2];
+        let mut spans = Vec::new();
+        let mut x = hints_area.x;
+        for (idx, (hint, action)) in app.keymap.footer_hints().into_iter().enumerate() {
+            if idx > 0 {
+                spans.push(Span::raw(" | "));
+                x = x.saturating_add(3);
+            }
+            let span = Span::raw(hint);
+            let width = (span.width() as u16).min(hints_area.right().saturating_sub(x));
+            if width > 0 {
+                let hint_area = Rect {
+                    x,
+                    width,
+                    ..hints_area
+                };
+                app.add_click_target(hint_area, ClickTarget::Action(action));
+            }
+            x = x.saturating_add(span.width() as u16);
+            spans.push(span);
+        }
+        f.render_widget(
+            Paragraph::new(Line::from(spans)).style(Style::default().fg(app.theme.hint)),
+            hints_area,
+        );
+        return;
+    }
+
     f.render_widget(
         Paragraph::new(app.get_keymap_msg())
             .wrap(Wrap { trim: true })
-            .style(Style::default().fg(tailwind::GRAY.c100)),
+            .style(Style::default().fg(app.theme.hint)),
         chunks[2],
     );
 }
@@ -0,0 +1,239 @@
+use std::{fmt, str::FromStr};
+
+use crate::model::UnitKind;
+
+/// What the target and logged amounts of a commitment are measured in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unit {
+    Hours,
+    Minutes,
+    Count,
+    /// Kilometres
+    Distance,
+    Custom(String),
+}
+
+impl Unit {
+    /// Rebuild the unit from its database columns
+    pub fn from_parts(kind: UnitKind, label: Option<&str>) -> Self {
+        match (kind, label) {
+            (UnitKind::Hours, _) => Unit::Hours,
+            (UnitKind::Minutes, _) => Unit::Minutes,
+            (UnitKind::Count, _) => Unit::Count,
+            (UnitKind::Distance, _) => Unit::Distance,
+            (UnitKind::Custom, Some(label)) => Unit::Custom(label.to_string()),
+            (UnitKind::Custom, None) => Unit::Count,
+        }
+    }
+
+    pub fn kind(&self) -> UnitKind {
+        match self {
+            Unit::Hours => UnitKind::Hours,
+            Unit::Minutes => UnitKind::Minutes,
+            Unit::Count => UnitKind::Count,
+            Unit::Distance => UnitKind::Distance,
+            Unit::Custom(_) => UnitKind::Custom,
+        }
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        match self {
+            Unit::Custom(label) => Some(label.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Suffix shown after amounts, empty for plain counts
+    pub fn suffix(&self) -> &str {
+        match self {
+            Unit::Hours => "h",
+            Unit::Minutes => "min",
+            Unit::Count => "",
+            Unit::Distance => "km",
+            Unit::Custom(label) => label.as_str(),
+        }
+    }
+
+    /// The number part of an amount, with the precision that makes sense for the unit
+    pub fn format_value(&self, value: f64) -> String {
+        let value = value + 0.0;
+        match self {
+            Unit::Minutes => format!("{:.0}", value),
+            Unit::Count | Unit::Custom(_) if value.fract() == 0.0 => format!("{:.0}", value),
+            _ => format!("{:.1}", value),
+        }
+    }
+
+    /// An amount with its unit suffix, e.g. `1.5 h` or `12 pages`
+    pub fn format(&self, value: f64) -> String {
+        let suffix = self.suffix();
+        if suffix.is_empty() {
+            self.format_value(value)
+        } else {
+            format!("{} {}", self.format_value(value), suffix)
+        }
+    }
+
+    /// Parse an amount typed by the user into this unit.
+    ///
+    /// Plain numbers are taken as already being in this unit. Time units also accept `1h30m`,
+    /// `1.5h` or `90m`, and distances accept `5km` or `500m`.
+    pub fn parse_amount(&self, input: &str) -> Result<f64, String> {
+        let input = input.trim().to_lowercase();
+        if input.is_empty() {
+            return Err("no amount given".to_string());
+        }
+
+        if let Ok(value) = input.parse::<f64>() {
+            return check_amount(value);
+        }
+
+        let value = match self {
+            Unit::Hours => parse_duration_minutes(&input).map(|minutes| minutes / 60.0),
+            Unit::Minutes => parse_duration_minutes(&input),
+            Unit::Distance => parse_distance_km(&input),
+            Unit::Count => None,
+            Unit::Custom(label) => input
+                .strip_suffix(label.to_lowercase().as_str())
+                .and_then(|number| number.trim().parse::<f64>().ok()),
+        };
+
+        match value {
+            Some(value) => check_amount(value),
+            None => Err(format!("cannot read '{input}' as an amount of {self}")),
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unit::Hours => write!(f, "hours"),
+            Unit::Minutes => write!(f, "minutes"),
+            Unit::Count => write!(f, "count"),
+            Unit::Distance => write!(f, "km"),
+            Unit::Custom(label) => write!(f, "{label}"),
+        }
+    }
+}
+
+impl FromStr for Unit {
+    type Err = String;
+
+    /// Known unit names map to their unit, anything else becomes a custom label
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let label = s.trim();
+        match label.to_lowercase().as_str() {
+            "" => Err("unit cannot be empty".to_string()),
+            "h" | "hour" | "hours" => Ok(Unit::Hours),
+            "m" | "min" | "mins" | "minute" | "minutes" => Ok(Unit::Minutes),
+            "count" | "times" | "x" => Ok(Unit::Count),
+            "km" | "distance" | "kilometre" | "kilometres" | "kilometer" | "kilometers" => {
+                Ok(Unit::Distance)
+            }
+            _ => Ok(Unit::Custom(label.to_string())),
+        }
+    }
+}
+
+fn check_amount(value: f64) -> Result<f64, String> {
+    if value.is_finite() && value >= 0.0 {
+        Ok(value)
+    } else {
+        Err("amount must be a non-negative number".to_string())
+    }
+}
+
+/// Read things like `1h30m`, `1.5h`, `45min` or `90m` as a number of minutes
+fn parse_duration_minutes(input: &str) -> Option<f64> {
+    let mut total = 0.0;
+    let mut number = String::new();
+    let mut rest = input.trim();
+
+    while !rest.is_empty() {
+        let split = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        number.clear();
+        number.push_str(&rest[..split]);
+        rest = rest[split..].trim_start();
+
+        let unit_len = rest
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(rest.len());
+        let unit = &rest[..unit_len];
+        rest = rest[unit_len..].trim_start();
+
+        let value = number.parse::<f64>().ok()?;
+        total += match unit {
+            "h" | "hr" | "hrs" | "hour" | "hours" => value * 60.0,
+            "m" | "min" | "mins" | "minute" | "minutes" => value,
+            _ => return None,
+        };
+    }
+
+    Some(total)
+}
+
+/// Read things like `5km` or `500m` as a number of kilometres
+fn parse_distance_km(input: &str) -> Option<f64> {
+    if let Some(number) = input.strip_suffix("km") {
+        number.trim().parse().ok()
+    } else if let Some(number) = input.strip_suffix('m') {
+        number
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .map(|metres| metres / 1000.0)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_numbers_are_in_the_unit() {
+        assert_eq!(Unit::Hours.parse_amount("1.5"), Ok(1.5));
+        assert_eq!(Unit::Minutes.parse_amount(" 45 "), Ok(45.0));
+        assert_eq!(Unit::Count.parse_amount("3"), Ok(3.0));
+    }
+
+    #[test]
+    fn durations_convert_to_the_time_unit() {
+        assert_eq!(Unit::Hours.parse_amount("1h30m"), Ok(1.5));
+        assert_eq!(Unit::Hours.parse_amount("1h 30m"), Ok(1.5));
+        assert_eq!(Unit::Hours.parse_amount("90m"), Ok(1.5));
+        assert_eq!(Unit::Hours.parse_amount("1.5H"), Ok(1.5));
+        assert_eq!(Unit::Minutes.parse_amount("1h30m"), Ok(90.0));
+        assert_eq!(Unit::Minutes.parse_amount("45min"), Ok(45.0));
+    }
+
+    #[test]
+    fn metres_only_mean_metres_for_distances() {
+        assert_eq!(Unit::Distance.parse_amount("500m"), Ok(0.5));
+        assert_eq!(Unit::Distance.parse_amount("5km"), Ok(5.0));
+        assert_eq!(Unit::Minutes.parse_amount("500m"), Ok(500.0));
+        assert_eq!(Unit::Hours.parse_amount("30m"), Ok(0.5));
+    }
+
+    #[test]
+    fn custom_units_accept_their_label() {
+        let pages = Unit::Custom("pages".to_string());
+        assert_eq!(pages.parse_amount("20 pages"), Ok(20.0));
+        assert_eq!(pages.parse_amount("20Pages"), Ok(20.0));
+        assert!(pages.parse_amount("20 words").is_err());
+    }
+
+    #[test]
+    fn rejects_what_is_not_an_amount() {
+        assert!(Unit::Hours.parse_amount("").is_err());
+        assert!(Unit::Hours.parse_amount("-1").is_err());
+        assert!(Unit::Hours.parse_amount("h").is_err());
+        assert!(Unit::Hours.parse_amount("5km").is_err());
+        assert!(Unit::Distance.parse_amount("1h").is_err());
+        assert!(Unit::Count.parse_amount("3x").is_err());
+    }
+}
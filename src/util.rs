@@ -1,6 +1,18 @@
-use chrono::{Datelike, Duration, Local, NaiveDate};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    sync::OnceLock,
+};
+
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
 use terminal_size::{Width, terminal_size};
 
+use crate::{
+    config::ProgressConfig,
+    model::{CumulativePoint, Goal, GoalDirection, WeeklyStat},
+    theme::no_color,
+};
+
 fn compute_bar_width(message_len: usize) -> usize {
     let default_bar = 20;
 
@@ -20,7 +32,7 @@ pub fn render_progress_bar(current: f64, target: f64, message_len: usize) -> Str
         return format!("[{}]", "-".repeat(width));
     }
 
-    let ratio = (current / target).max(0.0).min(1.0);
+    let ratio = (current / target).clamp(0.0, 1.0);
 
     let filled = (ratio * width as f64).round() as usize;
     let empty = width - filled;
@@ -28,23 +40,326 @@ pub fn render_progress_bar(current: f64, target: f64, message_len: usize) -> Str
     format!("[{}{}]", "#".repeat(filled), "-".repeat(empty))
 }
 
-const RED: &str = "\x1b[31m";
-const YELLOW: &str = "\x1b[33m";
-const GREEN: &str = "\x1b[32m";
+/// How good a number printed on the command line is, shown as green, yellow or red
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tone {
+    Good,
+    Warn,
+    Bad,
+}
 
-#[inline]
-pub fn color_for_pct(pct: f64) -> &'static str {
-    if pct < 25.0 {
-        RED
-    } else if pct < 75.0 {
-        YELLOW
+impl Tone {
+    fn ansi(self) -> &'static str {
+        match self {
+            Tone::Good => "\x1b[32m",
+            Tone::Warn => "\x1b[33m",
+            Tone::Bad => "\x1b[31m",
+        }
+    }
+}
+
+/// `text` in the colour of `tone`, or plain when `NO_COLOR` is set
+pub fn paint(text: impl Display, tone: Tone) -> String {
+    if no_color() {
+        text.to_string()
     } else {
-        GREEN
+        format!("{}{text}\x1b[0m", tone.ansi())
     }
 }
 
+#[inline]
+pub fn tone_for_pct(pct: f64, direction: GoalDirection, progress: &ProgressConfig) -> Tone {
+    match direction {
+        GoalDirection::AtLeast => {
+            if pct < progress.low {
+                Tone::Bad
+            } else if pct < progress.high {
+                Tone::Warn
+            } else {
+                Tone::Good
+            }
+        }
+        // Limits are fine while well under, close to the cap is a warning and over it is bad
+        GoalDirection::AtMost => {
+            if pct > 100.0 {
+                Tone::Bad
+            } else if pct >= progress.high {
+                Tone::Warn
+            } else {
+                Tone::Good
+            }
+        }
+    }
+}
+
+static WEEK_START: OnceLock<Weekday> = OnceLock::new();
+
+/// Make weeks start on `start` from now on, only the first call counts
+pub fn set_week_start(start: Weekday) {
+    let _ = WEEK_START.set(start);
+}
+
+/// The day weeks start on, from `week.start` in the config and Monday until that is set
+pub fn week_start() -> Weekday {
+    WEEK_START.get().copied().unwrap_or(Weekday::Mon)
+}
+
+/// The first day of the week of `date` when weeks start on `start`
+pub fn week_start_of(date: NaiveDate, start: Weekday) -> NaiveDate {
+    date - Duration::days(date.weekday().days_since(start) as i64)
+}
+
+/// The first day of the week of `date`, a Monday unless `week.start` says otherwise
+#[inline]
+pub fn monday_of(date: NaiveDate) -> NaiveDate {
+    week_start_of(date, week_start())
+}
+
 #[inline]
 pub fn get_monday_this_week() -> NaiveDate {
-    let today = Local::now().date_naive();
-    today - Duration::days(today.weekday().num_days_from_monday() as i64)
+    monday_of(Local::now().date_naive())
+}
+
+/// Sum the totals of single days into their weeks, oldest week first, with every day that has a
+/// total as a session
+pub fn weekly_stats_from_days(days: &[(NaiveDate, f64)]) -> Vec<WeeklyStat> {
+    let mut weeks: Vec<WeeklyStat> = Vec::new();
+    let mut days = days.to_vec();
+    days.sort_by_key(|(day, _)| *day);
+    for (day, hours) in days {
+        let week_start = monday_of(day);
+        match weeks.last_mut() {
+            Some(week) if week.week_start == week_start => {
+                week.total_hours += hours;
+                week.sessions += 1;
+            }
+            _ => weeks.push(WeeklyStat {
+                week_start,
+                total_hours: hours,
+                sessions: 1,
+            }),
+        }
+    }
+    weeks
+}
+
+/// All the mondays from `start_monday` up to and including `end_monday`.
+pub fn weeks_between(start_monday: NaiveDate, end_monday: NaiveDate) -> Vec<NaiveDate> {
+    let mut weeks = Vec::new();
+    let mut current = start_monday;
+    while current <= end_monday {
+        weeks.push(current);
+        current += Duration::weeks(1);
+    }
+    weeks
+}
+
+/// All the mondays from the start of `goal` up to and including the current week, or its end week
+/// when that is over.
+pub fn weeks_since(goal: &Goal) -> Vec<NaiveDate> {
+    weeks_between(goal.start_monday, goal.last_monday(get_monday_this_week()))
+        .into_iter()
+        .filter(|week| goal.counts_week(*week))
+        .collect()
+}
+
+/// Hours done in every week from `start_monday` to `end_monday`, with zero for weeks without any
+/// log.
+pub fn weekly_hours_between(
+    start_monday: NaiveDate,
+    end_monday: NaiveDate,
+    stats: &[WeeklyStat],
+) -> Vec<(NaiveDate, f64)> {
+    let hours_by_week: HashMap<NaiveDate, f64> = stats
+        .iter()
+        .map(|s| (s.week_start, s.total_hours))
+        .collect();
+
+    weeks_between(start_monday, end_monday)
+        .into_iter()
+        .map(|week| (week, hours_by_week.get(&week).copied().unwrap_or(0.0)))
+        .collect()
+}
+
+/// Hours done in every week of [`weeks_since()`], with zero for weeks without any log.
+pub fn weekly_hours_since(goal: &Goal, stats: &[WeeklyStat]) -> Vec<(NaiveDate, f64)> {
+    weekly_hours_between(
+        goal.start_monday,
+        goal.last_monday(get_monday_this_week()),
+        stats,
+    )
+    .into_iter()
+    .filter(|(week, _)| goal.counts_week(*week))
+    .collect()
+}
+
+/// Running totals of done and required hours for every week of [`weeks_since()`].
+///
+/// Weeks without any log are included with zero hours so the series has no gaps.
+pub fn cumulative_series(goal: &Goal, stats: &[WeeklyStat]) -> Vec<CumulativePoint> {
+    let mut done = 0.0;
+    let mut required = 0.0;
+    let mut balance = 0.0;
+    weekly_hours_since(goal, stats)
+        .into_iter()
+        .map(|(week_start, week_hours)| {
+            done += week_hours;
+            required += goal.weekly_target_hours;
+            balance = goal.carry_over.carry(balance) + week_hours - goal.weekly_target_hours;
+            CumulativePoint {
+                week_start,
+                week_hours,
+                done,
+                required,
+                balance,
+            }
+        })
+        .collect()
+}
+
+/// Done minus required hours at the end of `weeks` (oldest first), with the balance of past weeks
+/// carried over according to the goal's carry-over policy.
+pub fn carried_balance(goal: &Goal, weeks: &[(NaiveDate, f64)]) -> f64 {
+    weeks.iter().fold(0.0, |balance, (_, hours)| {
+        goal.carry_over.carry(balance) + hours - goal.weekly_target_hours
+    })
+}
+
+/// Sessions done and required over [`weeks_since()`], if the goal has a sessions target.
+pub fn session_totals(goal: &Goal, stats: &[WeeklyStat]) -> Option<(i64, i64)> {
+    let target = goal.weekly_target_sessions?;
+    let last_monday = goal.last_monday(get_monday_this_week());
+    let done = stats
+        .iter()
+        .filter(|s| {
+            s.week_start >= goal.start_monday
+                && s.week_start <= last_monday
+                && goal.counts_week(s.week_start)
+        })
+        .map(|s| s.sessions)
+        .sum();
+    let required = target * weeks_since(goal).len() as i64;
+
+    Some((done, required))
+}
+
+/// Order `items` as a tree, every item followed by its children, along with its depth.
+///
+/// Siblings keep their order in `items`, and items whose parent is not in `items` become roots.
+pub fn tree_order<T>(
+    items: Vec<T>,
+    id: impl Fn(&T) -> i64,
+    parent_id: impl Fn(&T) -> Option<i64>,
+) -> Vec<(T, usize)> {
+    let ids: HashSet<i64> = items.iter().map(&id).collect();
+    let mut children: HashMap<Option<i64>, Vec<usize>> = HashMap::new();
+    for (idx, item) in items.iter().enumerate() {
+        let parent = parent_id(item).filter(|p| ids.contains(p) && *p != id(item));
+        children.entry(parent).or_default().push(idx);
+    }
+
+    let mut order = Vec::with_capacity(items.len());
+    let mut stack: Vec<(usize, usize)> = children
+        .get(&None)
+        .map(|roots| roots.iter().rev().map(|&idx| (idx, 0)).collect())
+        .unwrap_or_default();
+    let mut seen = HashSet::new();
+    while let Some((idx, depth)) = stack.pop() {
+        if !seen.insert(idx) {
+            continue;
+        }
+        order.push((idx, depth));
+        if let Some(kids) = children.get(&Some(id(&items[idx]))) {
+            stack.extend(kids.iter().rev().map(|&kid| (kid, depth + 1)));
+        }
+    }
+
+    let mut slots: Vec<Option<T>> = items.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .filter_map(|(idx, depth)| slots[idx].take().map(|item| (item, depth)))
+        .collect()
+}
+
+/// Positions of the characters of `text` that match `query` in order, ignoring case, or `None`
+/// when they don't all appear. Each character is matched as early as possible.
+pub fn fuzzy_positions(query: &str, text: &str) -> Option<Vec<usize>> {
+    let mut positions = Vec::new();
+    let mut chars = text.chars().enumerate();
+    for q in query.chars().filter(|c| !c.is_whitespace()) {
+        let (pos, _) = chars.find(|(_, c)| c.to_lowercase().eq(q.to_lowercase()))?;
+        positions.push(pos);
+    }
+    Some(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn mondays_start_their_own_week() {
+        // 2026-10-12 is a Monday, the 11th the Sunday before it
+        assert_eq!(monday_of(date("2026-10-12")), date("2026-10-12"));
+        assert_eq!(monday_of(date("2026-10-18")), date("2026-10-12"));
+        assert_eq!(monday_of(date("2026-10-11")), date("2026-10-05"));
+    }
+
+    #[test]
+    fn weeks_can_start_on_sunday() {
+        let start = Weekday::Sun;
+        assert_eq!(week_start_of(date("2026-10-11"), start), date("2026-10-11"));
+        assert_eq!(week_start_of(date("2026-10-17"), start), date("2026-10-11"));
+        assert_eq!(week_start_of(date("2026-10-18"), start), date("2026-10-18"));
+    }
+
+    #[test]
+    fn days_are_summed_into_their_weeks() {
+        let stats = weekly_stats_from_days(&[
+            (date("2026-10-12"), 1.0),
+            (date("2026-10-11"), 2.0),
+            (date("2026-10-18"), 0.5),
+            (date("2026-10-05"), 1.5),
+        ]);
+        let weeks: Vec<_> = stats
+            .iter()
+            .map(|s| (s.week_start, s.total_hours, s.sessions))
+            .collect();
+        assert_eq!(
+            weeks,
+            [(date("2026-10-05"), 3.5, 2), (date("2026-10-12"), 1.5, 2)]
+        );
+    }
+
+    #[test]
+    fn fuzzy_matches_characters_in_order_ignoring_case() {
+        assert_eq!(fuzzy_positions("thes", "Thesis"), Some(vec![0, 1, 2, 3]));
+        assert_eq!(fuzzy_positions("tss", "Thesis"), Some(vec![0, 3, 5]));
+        assert_eq!(fuzzy_positions("GYM", "gym"), Some(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn fuzzy_needs_every_character_in_order() {
+        assert_eq!(fuzzy_positions("sht", "Thesis"), None);
+        assert_eq!(fuzzy_positions("thesiss", "Thesis"), None);
+        assert_eq!(fuzzy_positions("x", ""), None);
+    }
+
+    #[test]
+    fn fuzzy_ignores_spaces_in_the_query() {
+        assert_eq!(fuzzy_positions("th s", "Thesis"), Some(vec![0, 1, 3]));
+        assert_eq!(fuzzy_positions("gs", "Gym session"), Some(vec![0, 4]));
+        assert_eq!(fuzzy_positions(" ", "Gym"), Some(vec![]));
+        assert_eq!(fuzzy_positions("", "Gym"), Some(vec![]));
+    }
+
+    #[test]
+    fn fuzzy_positions_count_characters_not_bytes() {
+        assert_eq!(fuzzy_positions("É", "Café"), Some(vec![3]));
+        assert_eq!(fuzzy_positions("é l", "Café au lait"), Some(vec![3, 8]));
+    }
 }